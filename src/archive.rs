@@ -0,0 +1,283 @@
+use std::borrow::Cow;
+use std::cmp;
+use std::str;
+
+use zip::{ZipMember, zipMembers};
+use sections::readUInt;
+
+const TAR_BLOCK: u64 = 512;
+const TAR_REGULAR: [u8; 3] = [b'0', 0, b'7']; //the type flags of regular files (0 in the oldest tars, 7 for contiguous files)
+const TAR_LONG_NAME: u8 = b'L';                 //GNU: the contents are the name of the next member
+const TAR_PAX: u8 = b'x';                       //POSIX: the contents are records like "path=..." for the next member
+const CPIO_TRAILER: &'static str = "TRAILER!!!";  //the name of the entry marking the end of a cpio archive
+const CPIO_REGULAR: u64 = 0o100000;
+const CPIO_TYPE: u64 = 0o170000;
+const AR_MAGIC: &'static [u8] = b"!<arch>\n";
+const AR_HEADER: u64 = 60;
+const RPM_LEAD: u64 = 96;
+const RPM_MAGIC: u64 = 0xedabeedb;
+const RPM_HEADER_MAGIC: u64 = 0x8eade801;
+
+/// A file in an archive: a zip archive (including JARs and APKs), a tar or cpio archive, an ar archive (including static
+/// libraries and Debian packages) or the payload of an RPM package.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub name: String,
+    offset: u64,            //where the contents are in the archive, for the formats that store them as they are
+    size: u64,
+    zip: Option<ZipMember>, //for the members of zip archives, which are usually compressed
+}
+
+impl Member {
+    fn new(name: String, offset: u64, size: u64) -> Member {
+        return Member { name: name, offset: offset, size: size, zip: None };
+    }
+
+    /// The contents of the member. They are borrowed from data except for the members of zip archives, which are
+    /// decompressed (or copied if they are stored as they are) up to limit bytes. None if they can't be read.
+    pub fn contents<'a>(&self, data: &'a [u8], limit: u64) -> Option<Cow<'a, [u8]>> {
+        if let Some(ref member) = self.zip {
+            return member.contentsUpTo(data, limit).map(Cow::Owned);
+        }
+        if self.offset > data.len() as u64 {
+            return None;
+        }
+        return Some(Cow::Borrowed(&data[self.offset as usize..cmp::min(self.offset + self.size, data.len() as u64) as usize])); //cut off members still give what there is of them
+    }
+}
+
+fn text(bytes: &[u8]) -> String { //a field ending at its first null byte
+    let end = bytes.iter().position(|byte| *byte == 0).unwrap_or(bytes.len());
+    return String::from_utf8_lossy(&bytes[..end]).into_owned();
+}
+
+fn number(bytes: &[u8], radix: u32) -> Option<u64> { //a number written out in ASCII, padded with spaces or null bytes
+    let digits = str::from_utf8(bytes).ok()?.trim_matches(|c| c == ' ' || c == '\0');
+    return if digits.is_empty() { Some(0) } else { u64::from_str_radix(digits, radix).ok() };
+}
+
+fn tarNumber(bytes: &[u8]) -> Option<u64> { //octal, or big endian binary after a 0x80 byte for numbers too large for the field
+    if bytes[0] & 0x80 != 0 {
+        return Some(bytes[1..].iter().fold(0, |value, byte| value << 8 | *byte as u64));
+    }
+    return number(bytes, 8);
+}
+
+fn isTarHeader(header: &[u8]) -> bool { //tar has no magic number (ustar adds one at 257, but older tars don't), so the checksum is checked
+    if header.len() < TAR_BLOCK as usize || header[0] == 0 {
+        return false;
+    }
+    let sum: u64 = header[..TAR_BLOCK as usize].iter().enumerate().map(|(index, byte)| if index >= 148 && index < 156 { b' ' as u64 } else { *byte as u64 }).sum(); //with the checksum field as spaces
+    return tarNumber(&header[148..156]) == Some(sum);
+}
+
+fn tarMembers(data: &[u8]) -> Option<Vec<Member>> {
+    if !isTarHeader(data) {
+        return None;
+    }
+    let mut members: Vec<Member> = Vec::new();
+    let mut longName: Option<String> = None;
+    let mut offset = 0;
+    while offset + TAR_BLOCK <= data.len() as u64 {
+        let header = &data[offset as usize..(offset + TAR_BLOCK) as usize];
+        if !isTarHeader(header) { //the two blocks of zeros at the end, or garbage
+            break;
+        }
+        let size = cmp::min(tarNumber(&header[124..136])?, data.len() as u64); //cut off members still give what there is of them
+        let contents = offset + TAR_BLOCK;
+        let mut name = text(&header[0..100]);
+        if &header[257..263] == b"ustar\0" && header[345] != 0 { //POSIX: a prefix for long paths (GNU tars, with "ustar  ", have other fields there)
+            name = format!("{}/{}", text(&header[345..500]), name);
+        }
+        if let Some(long) = longName.take() {
+            name = long;
+        }
+        let body = &data[contents as usize..cmp::min(contents + size, data.len() as u64) as usize];
+        match header[156] {
+            TAR_LONG_NAME => longName = Some(text(body)),
+            TAR_PAX => {
+                for record in String::from_utf8_lossy(body).split('\n') { //"length key=value"
+                    if let Some(path) = record.splitn(2, ' ').nth(1).and_then(|record| if record.starts_with("path=") { Some(&record[5..]) } else { None }) {
+                        longName = Some(String::from(path));
+                    }
+                }
+            },
+            kind if TAR_REGULAR.contains(&kind) => members.push(Member::new(name, contents, size)),
+            _ => {}, //directories, links, devices...
+        }
+        offset = contents + (size + TAR_BLOCK - 1) / TAR_BLOCK * TAR_BLOCK;
+    }
+    return Some(members);
+}
+
+fn cpioMembers(data: &[u8]) -> Option<Vec<Member>> { //the "new" (070701, or 070702 with checksums) and "portable" (070707) formats
+    let (newFormat, headerSize) = match data.get(0..6) {
+        Some(b"070701") | Some(b"070702") => (true, 110),
+        Some(b"070707") => (false, 76),
+        _ => return None,
+    };
+    let align = |offset: u64| if newFormat { (offset + 3) / 4 * 4 } else { offset }; //the new format pads names and contents to 4 bytes
+    let mut members: Vec<Member> = Vec::new();
+    let mut offset = 0;
+    while offset + headerSize <= data.len() as u64 {
+        let field = |start: u64, length: u64| number(&data[(offset + start) as usize..(offset + start + length) as usize], if newFormat { 16 } else { 8 });
+        let (mode, nameSize, size) = if newFormat {
+            (field(14, 8)?, field(94, 8)?, field(54, 8)?)
+        }
+        else {
+            (field(18, 6)?, field(59, 6)?, field(65, 11)?)
+        };
+        let name = offset + headerSize;
+        if name + nameSize > data.len() as u64 {
+            break;
+        }
+        let name = text(&data[name as usize..(name + nameSize) as usize]);
+        if name == CPIO_TRAILER {
+            break;
+        }
+        let contents = align(offset + headerSize + nameSize);
+        if mode & CPIO_TYPE == CPIO_REGULAR {
+            members.push(Member::new(name, contents, size));
+        }
+        offset = align(contents + size);
+        if data.get(offset as usize..offset as usize + 5) != Some(b"07070") {
+            break;
+        }
+    }
+    return Some(members);
+}
+
+fn arMembers(data: &[u8]) -> Option<Vec<Member>> {
+    if !data.starts_with(AR_MAGIC) {
+        return None;
+    }
+    let mut members: Vec<Member> = Vec::new();
+    let mut longNames: &[u8] = &[]; //GNU: the names too long for the header, each ending in "/\n"
+    let mut offset = AR_MAGIC.len() as u64;
+    while offset + AR_HEADER <= data.len() as u64 {
+        let header = &data[offset as usize..(offset + AR_HEADER) as usize];
+        if &header[58..60] != b"`\n" {
+            break;
+        }
+        let mut size = number(&header[48..58], 10)?;
+        let mut contents = offset + AR_HEADER;
+        let field = String::from_utf8_lossy(&header[0..16]).trim_end().to_string();
+        let name = if field == "//" {
+            longNames = &data[contents as usize..cmp::min(contents + size, data.len() as u64) as usize];
+            None
+        }
+        else if field.starts_with("#1/") { //BSD: the name is at the start of the contents
+            let length = number(field[3..].as_bytes(), 10)?;
+            let name = text(&data[contents as usize..cmp::min(contents + length, data.len() as u64) as usize]);
+            contents += length;
+            size = size.saturating_sub(length);
+            Some(name)
+        }
+        else if field.len() > 1 && field.starts_with('/') && field[1..].bytes().all(|byte| byte.is_ascii_digit()) { //GNU: an offset into the long names
+            let start = number(field[1..].as_bytes(), 10)? as usize;
+            let name = longNames.get(start..).map_or(&[][..], |names| &names[..names.windows(2).position(|end| end == b"/\n").unwrap_or(names.len())]);
+            Some(String::from_utf8_lossy(name).into_owned())
+        }
+        else if field == "/" || field == "/SYM64/" { //the symbol table, which keeps its name
+            Some(field)
+        }
+        else {
+            Some(String::from(field.trim_end_matches('/')))
+        };
+        if let Some(name) = name {
+            members.push(Member::new(name, contents, size));
+        }
+        offset = contents + size;
+        offset += offset % 2; //members are padded to an even offset
+    }
+    return Some(members);
+}
+
+fn rpmMembers(data: &[u8]) -> Option<Vec<Member>> { //the lead, the signature header (padded to 8 bytes), the header, then the (compressed) cpio archive of the files
+    if readUInt(data, 0, 4, true)? != RPM_MAGIC {
+        return None;
+    }
+    let mut offset = RPM_LEAD;
+    for header in 0..2 {
+        if readUInt(data, offset, 4, true)? != RPM_HEADER_MAGIC {
+            return None;
+        }
+        let (count, size) = (readUInt(data, offset + 8, 4, true)?, readUInt(data, offset + 12, 4, true)?);
+        offset += 16 + count * 16 + size;
+        if header == 0 {
+            offset = (offset + 7) / 8 * 8;
+        }
+    }
+    if offset > data.len() as u64 {
+        return None;
+    }
+    return Some(vec![Member::new(String::from("payload"), offset, data.len() as u64 - offset)]);
+}
+
+/// Lists the files in data if it is an archive in one of the formats of `Member`. Zip archives are only recognized when
+/// they start with a member, so executables with a zip archive appended (like self extracting archives) are still
+/// treated as executables. Returns None if data isn't an archive.
+pub fn archiveMembers(data: &[u8]) -> Option<Vec<Member>> {
+    if data.starts_with(b"PK\x03\x04") || data.starts_with(b"PK\x05\x06") { //the first member, or the end of an empty archive
+        if let Some(members) = zipMembers(data) {
+            return Some(members.into_iter().filter(|member| !member.isDirectory())
+                .map(|member| Member { name: member.name.clone(), offset: member.offset, size: member.size, zip: Some(member) }).collect());
+        }
+    }
+    return arMembers(data)
+        .or_else(|| cpioMembers(data))
+        .or_else(|| rpmMembers(data))
+        .or_else(|| tarMembers(data));
+}
+
+#[cfg(test)]
+mod tests {
+    use testing::readFile;
+    use super::archiveMembers;
+
+    fn listing(path: &str) -> Vec<(String, Vec<u8>)> { //the names and contents of the members of an archive
+        let data = readFile(path);
+        return archiveMembers(&data).unwrap().iter().map(|member| (member.name.clone(), member.contents(&data, 1 << 20).unwrap().into_owned())).collect();
+    }
+
+    #[test]
+    fn testTarMembers() {
+        let members = listing("./testCases/bundle.tar");
+        let names: Vec<&str> = members.iter().map(|&(ref name, _)| &name[..]).collect();
+        assert_eq!(vec!["bundle/notes.txt", "bundle/inner.zip", "bundle/a/very/long/path/that/does/not/fit/in/the/hundred/bytes/of/the/name/field/of/a/tar/header/deep.txt"], names);
+        assert_eq!(&b"Remember the milk\0"[..], &members[0].1[..]);
+    }
+
+    #[test]
+    fn testCpioMembers() {
+        let members = listing("./testCases/bundle.cpio");
+        assert_eq!(vec![(String::from("etc/motd"), b"Welcome to the cpio archive\0".to_vec()), (String::from("etc/issue"), b"Portable Linux 1.0\0".to_vec())], members);
+    }
+
+    #[test]
+    fn testArMembers() {
+        let members = listing("./testCases/libnames.a");
+        let names: Vec<&str> = members.iter().map(|&(ref name, _)| &name[..]).collect();
+        assert_eq!(vec!["short.txt", "a_member_with_a_rather_long_name.txt"], names);
+        assert_eq!(&b"long name member\0\n"[..], &members[1].1[..]);
+    }
+
+    #[test]
+    fn testPackageMembers() {
+        let names: Vec<String> = listing("./testCases/hello.deb").into_iter().map(|(name, _)| name).collect();
+        assert_eq!(vec!["debian-binary", "control.tar.xz", "data.tar.xz"], names);
+        let members = listing("./testCases/hello.rpm");
+        assert_eq!(1, members.len());
+        assert_eq!(("payload", &b"\x1f\x8b"[..]), (&members[0].0[..], &members[0].1[0..2]));
+    }
+
+    #[test]
+    fn testZipMembers() {
+        let data = readFile("./testCases/hello.jar");
+        let members = archiveMembers(&data).unwrap();
+        assert_eq!(2, members.len()); //without the directory
+        assert_eq!(b"Manifest", &members[0].contents(&data, 8).unwrap()[..]); //cut short
+        assert_eq!(None, archiveMembers(&readFile("./testCases/a.out")));
+        assert_eq!(None, archiveMembers(&readFile("./testCases/log.txt")));
+    }
+}
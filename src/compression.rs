@@ -0,0 +1,312 @@
+use std::io;
+use std::io::{BufRead, BufReader, Read};
+
+use flate2::bufread::{GzDecoder, MultiGzDecoder, ZlibDecoder};
+use xz2::bufread::XzDecoder;
+use xz2::stream::{Action, Status, Stream};
+use bzip2::bufread::{BzDecoder, MultiBzDecoder};
+use zstd::stream::read::Decoder as ZstdDecoder;
+
+use scanner::{Scanner, FoundString};
+
+pub const MAX_DECOMPRESSED_SIZE: u64 = 1 << 30; //streams are only decompressed up to this size, so a decompression bomb can't use up the memory
+const MAX_MAGIC: usize = 13; //the most bytes needed to tell the formats apart
+const MAX_LZMA_SIZE: u64 = 1 << 40; //an LZMA header giving a bigger size than this is taken to be something else
+const PROBE_SIZE: u64 = 1 << 16; //how much of a stream has to decompress before it is taken to be compressed
+
+/// The compression formats that are decompressed, told apart by their magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    Gzip,   //1f 8b, then 08 for deflate
+    Zlib,   //a 2 byte header whose first byte gives deflate and a window size, and which is a multiple of 31
+    Xz,     //fd 37 7a 58 5a 00
+    Bzip2,  //BZh and the block size, 1 to 9
+    Zstd,   //28 b5 2f fd
+    Lzma,   //the .lzma (LZMA_Alone) header: 5d for the usual settings, the dictionary size and the size, -1 if unknown
+}
+
+impl Compression {
+    /// The format data is compressed with, judging by its first bytes. None if it isn't one of the formats.
+    pub fn detect(data: &[u8]) -> Option<Compression> {
+        if data.starts_with(b"\x1f\x8b\x08") {
+            return Some(Compression::Gzip);
+        }
+        if data.starts_with(b"\xfd7zXZ\0") {
+            return Some(Compression::Xz);
+        }
+        if data.starts_with(b"BZh") && data.len() > 3 && data[3] >= b'1' && data[3] <= b'9' {
+            return Some(Compression::Bzip2);
+        }
+        if data.starts_with(b"\x28\xb5\x2f\xfd") {
+            return Some(Compression::Zstd);
+        }
+        if data.len() >= 13 && data[0] == 0x5d {
+            let dictionary = data[1..5].iter().rev().fold(0u32, |value, &byte| value << 8 | byte as u32);
+            let size = data[5..13].iter().rev().fold(0u64, |value, &byte| value << 8 | byte as u64);
+            let rounded = dictionary & !(dictionary >> 1); //dictionaries are a power of 2, or 1.5 times one
+            if dictionary >= 1 << 12 && (dictionary == rounded || dictionary == rounded | rounded >> 1) && (size == u64::max_value() || size < MAX_LZMA_SIZE) {
+                return Some(Compression::Lzma);
+            }
+        }
+        if data.len() >= 2 && data[0] & 0x0f == 8 && data[0] >> 4 <= 7 && data[1] & 0x20 == 0 && (data[0] as u16 * 256 + data[1] as u16) % 31 == 0 { //deflate, a window of at most 32KB, no preset dictionary
+            return Some(Compression::Zlib);
+        }
+        return None;
+    }
+
+    pub fn name(&self) -> &'static str {
+        return match *self {
+            Compression::Gzip => "gzip",
+            Compression::Zlib => "zlib",
+            Compression::Xz => "xz",
+            Compression::Bzip2 => "bzip2",
+            Compression::Zstd => "zstd",
+            Compression::Lzma => "lzma",
+        };
+    }
+
+    /// Wraps reader, which gives data compressed in this format, in a reader that gives the decompressed data. Gzip, xz
+    /// and bzip2 files with several streams (e.g. from `cat a.gz b.gz`) are decompressed whole. Only the compressed
+    /// bytes that have been decompressed are consumed from reader.
+    pub fn decoder<'a, R: BufRead + 'a>(&self, reader: R) -> io::Result<Box<dyn Read + 'a>> {
+        return Ok(match *self {
+            Compression::Gzip => Box::new(MultiGzDecoder::new(reader)),
+            Compression::Zlib => Box::new(ZlibDecoder::new(reader)),
+            Compression::Xz => Box::new(XzDecoder::new_multi_decoder(reader)),
+            Compression::Bzip2 => Box::new(MultiBzDecoder::new(reader)),
+            Compression::Zstd => Box::new(ZstdDecoder::with_buffer(reader)?),
+            Compression::Lzma => Box::new(LzmaDecoder { reader: reader, stream: Stream::new_lzma_decoder(u64::max_value())?, finished: false }),
+        });
+    }
+
+    /// `decoder`, but for a single stream: whatever follows its end is left alone rather than taken for the next one.
+    pub fn streamDecoder<'a, R: BufRead + 'a>(&self, reader: R) -> io::Result<Box<dyn Read + 'a>> {
+        return Ok(match *self {
+            Compression::Gzip => Box::new(GzDecoder::new(reader)),
+            Compression::Xz => Box::new(XzDecoder::new(reader)),
+            Compression::Bzip2 => Box::new(BzDecoder::new(reader)),
+            Compression::Zstd => Box::new(ZstdDecoder::with_buffer(reader)?.single_frame()),
+            _ => self.decoder(reader)?,
+        });
+    }
+}
+
+struct LzmaDecoder<R: BufRead> { //xz2's decoder goes on past the end of an LZMA stream, and fails if anything follows it
+    reader: R,
+    stream: Stream,
+    finished: bool,
+}
+
+impl<R: BufRead> Read for LzmaDecoder<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        while !self.finished && buffer.len() > 0 {
+            let (inBefore, outBefore) = (self.stream.total_in(), self.stream.total_out());
+            let (status, ended) = {
+                let input = self.reader.fill_buf()?;
+                (self.stream.process(input, buffer, if input.len() == 0 { Action::Finish } else { Action::Run }), input.len() == 0)
+            };
+            self.reader.consume((self.stream.total_in() - inBefore) as usize);
+            self.finished = status? == Status::StreamEnd;
+            let read = (self.stream.total_out() - outBefore) as usize;
+            if read > 0 {
+                return Ok(read);
+            }
+            if ended && !self.finished {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated lzma stream"));
+            }
+        }
+        return Ok(0);
+    }
+}
+
+/// Decompresses data if it is compressed in one of the formats of `Compression`, cut short at `MAX_DECOMPRESSED_SIZE`.
+/// If the data goes bad (e.g. the file is truncated) what could be decompressed before then is returned. None if data
+/// isn't compressed or nothing could be decompressed.
+pub fn decompress(data: &[u8]) -> Option<(Compression, Vec<u8>)> {
+    return decompressUpTo(data, MAX_DECOMPRESSED_SIZE).map(|(compression, decompressed, _)| (compression, decompressed));
+}
+
+/// `decompress`, cut short at limit bytes instead. Also returns how many bytes of data the decoder read, past which
+/// what's left of it (if the limit was reached or the data went bad) is still compressed.
+pub fn decompressUpTo(data: &[u8], limit: u64) -> Option<(Compression, Vec<u8>, usize)> {
+    let compression = Compression::detect(data)?;
+    let mut decompressed: Vec<u8> = Vec::new();
+    let mut input = data;
+    let _ = compression.decoder(&mut input).and_then(|decoder| decoder.take(limit).read_to_end(&mut decompressed)); //on an error what was decompressed before the data went bad is kept
+    if decompressed.len() == 0 {
+        return None;
+    }
+    return Some((compression, decompressed, data.len() - input.len()));
+}
+
+/// Decompresses the stream at the start of data, which may go on past its end, cut short at limit bytes. Unlike
+/// `decompress` the whole stream has to be good (up to the limit), as it is used to look for streams at every offset of
+/// a file where something that looks like a header is far more likely to be chance. None if it isn't a good stream.
+pub fn decompressStream(data: &[u8], limit: u64) -> Option<(Compression, Vec<u8>)> {
+    let compression = Compression::detect(data)?;
+    let mut decompressed: Vec<u8> = Vec::new();
+    if compression.streamDecoder(data).and_then(|decoder| decoder.take(limit).read_to_end(&mut decompressed)).is_err() || decompressed.len() == 0 {
+        return None;
+    }
+    return Some((compression, decompressed));
+}
+
+/// Wraps reader in a decoder if what it gives is compressed in one of the formats of `Compression`, so a stream (like
+/// stdin) can be searched decompressed without reading it all first. Returns the format, None if the stream isn't
+/// compressed in which case the reader gives it as it is.
+pub fn decompressReader<'a, R: Read + 'a>(mut reader: R) -> io::Result<(Option<Compression>, Box<dyn Read + 'a>)> {
+    let mut magic: Vec<u8> = Vec::new();
+    (&mut reader).take(MAX_MAGIC as u64).read_to_end(&mut magic)?;
+    let stream = io::Cursor::new(magic.clone()).chain(reader); //the magic bytes are put back in front
+    return match Compression::detect(&magic) {
+        Some(compression) => Ok((Some(compression), compression.decoder(BufReader::new(stream))?)),
+        None => Ok((None, Box::new(stream))),
+    };
+}
+
+struct Counted<R: BufRead> { //counts the bytes consumed from reader
+    reader: R,
+    consumed: u64,
+}
+
+impl<R: BufRead> Read for Counted<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buffer)?;
+        self.consumed += read as u64;
+        return Ok(read);
+    }
+}
+
+impl<R: BufRead> BufRead for Counted<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        return self.reader.fill_buf();
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.reader.consume(amount);
+        self.consumed += amount as u64;
+    }
+}
+
+fn isCompressed(compression: Compression, probe: &[u8], more: bool) -> bool { //whether probe, the start of a stream (followed by more of it if more), decompresses, so text that happens to start like a zlib header isn't taken for it
+    let mut input = Counted { reader: probe, consumed: 0 };
+    return match compression.decoder(&mut input).and_then(|mut decoder| io::copy(&mut decoder, &mut io::sink())) {
+        Ok(decompressed) => decompressed > 0,
+        Err(_) => more && input.consumed == probe.len() as u64, //ran out of probe rather than into bad data
+    };
+}
+
+/// Scans a stream (like stdin) a buffer at a time, decompressing it first if it is compressed in one of the formats of
+/// `Compression` (and its first 64KB decompress, otherwise it is taken to be something that happens to start like
+/// compressed data), and passes the strings found to callback. What it decompresses to is scanned up to limit bytes,
+/// with the strings tagged with stream 0; past the limit, or if the data goes bad, the compressed data that is left is
+/// scanned as it is, with offsets into the stream (from where the decoder got to, which can be a little past what it
+/// gave out). With a limit of 0 the stream is scanned as it is. Returns how many bytes were decompressed, or the first
+/// error reading the stream.
+pub fn scanDecompressed<R: Read, F: FnMut(FoundString)>(scanner: &Scanner, reader: R, limit: u64, mut callback: F) -> io::Result<u64> {
+    let mut probe: Vec<u8> = Vec::new();
+    let mut reader = reader;
+    (&mut reader).take(PROBE_SIZE).read_to_end(&mut probe)?;
+    let more = probe.len() as u64 == PROBE_SIZE;
+    let compression = if limit > 0 { Compression::detect(&probe).filter(|compression| isCompressed(*compression, &probe, more)) } else { None };
+    let mut input = Counted { reader: BufReader::new(io::Cursor::new(probe).chain(reader)), consumed: 0 }; //the probe is put back in front
+    let mut left = limit;
+    if let Some(compression) = compression {
+        let mut decompressed = compression.decoder(&mut input)?.take(limit);
+        for result in scanner.scanReader(&mut decompressed) {
+            match result {
+                Ok(found) => callback(FoundString { stream: Some(0), ..found }),
+                Err(_) => break, //what could be decompressed before the data went bad is kept, an error reading the stream comes up again below
+            }
+        }
+        left = decompressed.limit();
+    }
+    let start = input.consumed;
+    for result in scanner.scanReader(input) {
+        let found = result?;
+        callback(FoundString { offset: found.offset + start, ..found });
+    }
+    return Ok(limit - left);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::{Read, Write};
+    use std::str;
+    use flate2::write::GzEncoder;
+    use flate2::Compression as Level;
+    use scanner::{ScanOptions, Scanner, FoundString};
+    use testing::readFile;
+    use super::{Compression, decompress, decompressStream, decompressReader, scanDecompressed};
+
+    #[test]
+    fn testDecompress() {
+        let expected = readFile("./testCases/log.txt");
+        for &(path, compression) in [("./testCases/log.txt.gz", Compression::Gzip), ("./testCases/log.txt.zz", Compression::Zlib), ("./testCases/log.txt.xz", Compression::Xz),
+                ("./testCases/log.txt.bz2", Compression::Bzip2), ("./testCases/log.txt.zst", Compression::Zstd), ("./testCases/log.txt.lzma", Compression::Lzma)].iter() {
+            assert_eq!(Some((compression, expected.clone())), decompress(&readFile(path)), "{}", path);
+        }
+        let truncated = readFile("./testCases/log.txt.gz");
+        let (_, partial) = decompress(&truncated[..truncated.len() / 2]).unwrap();
+        assert!(expected.starts_with(&partial) && partial.len() > 0);
+        assert_eq!(None, decompress(&expected));
+        assert_eq!(None, decompress(b"\x1f\x8b\x08 but not gzip"));
+    }
+
+    #[test]
+    fn testDecompressStream() {
+        let data = readFile("./testCases/firmware.bin");
+        let (compression, decompressed) = decompressStream(&data[0x65..], 1 << 20).unwrap(); //followed by more of the file
+        assert_eq!((Compression::Zlib, &b"kernel cmdline: console=ttyS0 root=/dev/mtdblock2\0"[..]), (compression, &decompressed[..50]));
+        assert_eq!(Some(Compression::Gzip), decompressStream(&data[0xc7..], 1 << 20).map(|(compression, _)| compression));
+        let (compression, decompressed) = decompressStream(&data[0x121..], 1 << 20).unwrap();
+        assert_eq!((Compression::Lzma, &b"lzma squashed config: telnetd enabled\0"[..]), (compression, &decompressed[..38]));
+        assert_eq!(10, decompressStream(&data[0x121..], 10).unwrap().1.len());
+        let gzip = readFile("./testCases/log.txt.gz");
+        assert_eq!(None, decompressStream(&gzip[..gzip.len() / 2], 1 << 20)); //unlike decompress a truncated stream is no good
+        assert_eq!(None, decompressStream(b"\x78\x9c not really zlib", 1 << 20));
+    }
+
+    #[test]
+    fn testDecompressReader() {
+        let (compression, mut reader) = decompressReader(File::open("./testCases/log.txt.xz").unwrap()).unwrap();
+        let mut decompressed: Vec<u8> = Vec::new();
+        reader.read_to_end(&mut decompressed).unwrap();
+        assert_eq!((Some(Compression::Xz), readFile("./testCases/log.txt")), (compression, decompressed));
+        let (compression, mut reader) = decompressReader(&b"plain"[..]).unwrap();
+        let mut data: Vec<u8> = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!((None, &b"plain"[..]), (compression, &data[..]));
+    }
+
+    #[test]
+    fn testScanDecompressed() {
+        let log = readFile("./testCases/log.txt");
+        let scanner = Scanner::new(ScanOptions::new().nullTerminated(false));
+        let mut strings: Vec<FoundString> = Vec::new();
+        assert_eq!(log.len() as u64, scanDecompressed(&scanner, File::open("./testCases/log.txt.gz").unwrap(), 1 << 20, |found| strings.push(found)).unwrap());
+        assert_eq!(scanner.scan(&log).map(|found| FoundString { stream: Some(0), ..found }).collect::<Vec<FoundString>>(), strings);
+
+        let mut stored = GzEncoder::new(Vec::new(), Level::none()); //stored rather than compressed, so the text is still there past the limit
+        stored.write_all(&log).unwrap();
+        let stored = stored.finish().unwrap();
+        let mut strings: Vec<FoundString> = Vec::new();
+        assert_eq!(1000, scanDecompressed(&scanner, &stored[..], 1000, |found| strings.push(found)).unwrap());
+        assert_eq!((Some(0), 0), (strings[0].stream, strings[0].offset));
+        let last = strings.last().unwrap();
+        assert_eq!((None, last.text.as_bytes()), (last.stream, &stored[last.offset as usize..last.offset as usize + last.length]));
+        assert_eq!(str::from_utf8(&log).unwrap().lines().last().unwrap(), last.text);
+        let mut strings: Vec<FoundString> = Vec::new();
+        assert_eq!(0, scanDecompressed(&scanner, &stored[..], 0, |found| strings.push(found)).unwrap());
+        assert_eq!(scanner.scan(&stored).collect::<Vec<FoundString>>(), strings);
+
+        let text = b"HKeep this line: a plain text note\n(Szall systems nominal, report follows here\n"; //both lines start like a zlib header
+        for start in [0, 35].iter() {
+            assert_eq!(Some(Compression::Zlib), Compression::detect(&text[*start..]));
+            let mut strings: Vec<FoundString> = Vec::new();
+            assert_eq!(0, scanDecompressed(&scanner, &text[*start..], 1 << 20, |found| strings.push(found)).unwrap());
+            assert_eq!(scanner.scan(&text[*start..]).collect::<Vec<FoundString>>(), strings);
+        }
+    }
+}
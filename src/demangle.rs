@@ -0,0 +1,82 @@
+use rustc_demangle;
+use cpp_demangle;
+
+const SWIFT_PREFIXES: [&'static str; 5] = ["_$s", "$s", "_$S", "$S", "_T0"]; //Swift 5, 4.2 and 4.0 (with the extra underscore Mach-O symbols get)
+
+/// Demangles a C++ (Itanium ABI), Rust (legacy or v0) or Swift symbol name. Returns None if name isn't a mangled name.
+///
+/// ```
+/// use rstrings::demangle;
+/// assert_eq!(Some(String::from("std::io::stdio::_print")), demangle("_ZN3std2io5stdio6_print17h8f2d5a4a3b1c0e9fE"));
+/// assert_eq!(None, demangle("hello world"));
+/// ```
+pub fn demangle(name: &str) -> Option<String> {
+    if let Ok(demangled) = rustc_demangle::try_demangle(name) { //Rust's legacy names look like C++ ones, so Rust is tried first to drop the hash
+        return Some(format!("{:#}", demangled));
+    }
+    if name.starts_with("_Z") || name.starts_with("__Z") { //Mach-O adds an underscore
+        let name = if name.starts_with("__Z") { &name[1..] } else { name };
+        return match cpp_demangle::Symbol::new(name.as_bytes()) {
+            Ok(symbol) => symbol.demangle(&cpp_demangle::DemangleOptions::default()).ok(),
+            Err(_) => None,
+        };
+    }
+    return demangleSwift(name);
+}
+
+fn demangleSwift(name: &str) -> Option<String> { //only the names of the entity and the types and modules it is in (e.g. main.Hello.greet), not its type
+    let prefix = SWIFT_PREFIXES.iter().find(|prefix| name.starts_with(*prefix))?;
+    let mangled = name[prefix.len()..].as_bytes();
+    let mut names: Vec<String> = Vec::new();
+    let mut index = 0;
+    while index < mangled.len() {
+        if names.len() > 0 && mangled[index].is_ascii_alphabetic() && index + 1 < mangled.len() && mangled[index+1].is_ascii_digit() {
+            index += 1; //the kind (struct, class, enum...) of the name before
+        }
+        if index >= mangled.len() || !mangled[index].is_ascii_digit() || mangled[index] == b'0' { //0 starts a name made of substitutions, which isn't supported
+            break;
+        }
+        let start = index;
+        while index < mangled.len() && mangled[index].is_ascii_digit() {
+            index += 1;
+        }
+        let length: usize = name[prefix.len()+start..prefix.len()+index].parse().ok()?;
+        if index.checked_add(length).is_none_or(|end| end > mangled.len()) {
+            return None;
+        }
+        names.push(String::from_utf8_lossy(&mangled[index..index+length]).into_owned());
+        index += length;
+    }
+    if names.len() < 2 { //a module and something in it
+        return None;
+    }
+    return Some(names.join("."));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::demangle;
+
+    #[test]
+    fn testDemangleCpp() {
+        assert_eq!(Some(String::from("foo::bar(int, char const*)")), demangle("_ZN3foo3barEiPKc"));
+        assert_eq!(Some(String::from("std::ios_base::Init::Init()")), demangle("__ZNSt8ios_base4InitC1Ev"));
+        assert_eq!(None, demangle("_Zombie"));
+    }
+
+    #[test]
+    fn testDemangleRust() {
+        assert_eq!(Some(String::from("searchFile::STATIC_FMTSTR")), demangle("_ZN10searchFile13STATIC_FMTSTR17h3c9e0f06d0a62d14E"));
+        assert_eq!(Some(String::from("std::fs::File::drop.2550")), demangle("_ZN13std..fs..File9drop.255017hb0fe64ef671ab904E"));
+        assert_eq!(Some(String::from("main::greet")), demangle("_RNvCs1234_4main5greet"));
+    }
+
+    #[test]
+    fn testDemangleSwift() {
+        assert_eq!(Some(String::from("main.Hello.greet")), demangle("$s4main5HelloV5greetyyF"));
+        assert_eq!(Some(String::from("Foundation.URL.path")), demangle("_$s10Foundation3URLV4pathSSvg"));
+        assert_eq!(None, demangle("$s4main"));
+        assert_eq!(None, demangle("$sign"));
+        assert_eq!(None, demangle("$s4main18446744073709551615x"));
+    }
+}
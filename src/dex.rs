@@ -0,0 +1,218 @@
+use std::cmp;
+use std::collections::HashMap;
+
+use encoding::Encoding;
+use scanner::FoundString;
+use sections::{readUInt, readUleb128};
+use java::decodeModifiedUtf8;
+
+const CONST_STRING: u64 = 0x1a;
+const CONST_STRING_JUMBO: u64 = 0x1b;
+const PACKED_SWITCH_PAYLOAD: u64 = 0x0100; //the tables some instructions point at, which sit among the instructions
+const SPARSE_SWITCH_PAYLOAD: u64 = 0x0200;
+const FILL_ARRAY_DATA_PAYLOAD: u64 = 0x0300;
+
+struct Header { //the counts and offsets of the tables needed, from the header
+    strings: (u64, u64),
+    types: (u64, u64),
+    protos: (u64, u64),
+    methods: (u64, u64),
+    classes: (u64, u64),
+}
+
+pub fn isDex(data: &[u8]) -> bool {
+    return data.len() >= 0x70 && &data[0..4] == b"dex\n" && data[7] == 0; //dex\n then a version like 035\0
+}
+
+fn parseHeader(data: &[u8]) -> Option<Header> {
+    if !isDex(data) {
+        return None;
+    }
+    let table = |offset: u64, size: u64| -> Option<(u64, u64)> { //a table of count entries of size bytes, None if it doesn't fit in the file
+        let (count, start) = (readUInt(data, offset, 4, false)?, readUInt(data, offset + 4, 4, false)?);
+        return if start + count * size <= data.len() as u64 { Some((count, start)) } else { None };
+    };
+    return Some(Header {
+        strings: table(0x38, 4)?,
+        types: table(0x40, 4)?,
+        protos: table(0x48, 12)?,
+        methods: table(0x58, 8)?,
+        classes: table(0x60, 32)?,
+    });
+}
+
+fn stringData(data: &[u8], header: &Header, index: u64) -> Option<(u64, usize, String)> { //the offset, length in bytes and text of a string
+    if index >= header.strings.0 {
+        return None;
+    }
+    let mut offset = readUInt(data, header.strings.1 + index * 4, 4, false)?;
+    readUleb128(data, &mut offset)?; //the length in UTF-16 code units
+    if offset >= data.len() as u64 {
+        return None;
+    }
+    let length = data[offset as usize..].iter().position(|byte| *byte == 0)?;
+    let text = decodeModifiedUtf8(&data[offset as usize..offset as usize + length])?;
+    return Some((offset, length, text));
+}
+
+fn typeName(data: &[u8], header: &Header, index: u64) -> Option<String> {
+    if index >= header.types.0 {
+        return None;
+    }
+    return stringData(data, header, readUInt(data, header.types.1 + index * 4, 4, false)?).map(|(_, _, text)| text);
+}
+
+fn methodName(data: &[u8], header: &Header, index: u64) -> Option<String> { //like smali writes it, e.g. Lcom/example/Hello;->main([Ljava/lang/String;)V
+    if index >= header.methods.0 {
+        return None;
+    }
+    let method = header.methods.1 + index * 8;
+    let read = |offset: u64, size: usize| readUInt(data, offset, size, false);
+    let (class, proto, name) = (read(method, 2)?, read(method + 2, 2)?, read(method + 4, 4)?);
+    if proto >= header.protos.0 {
+        return None;
+    }
+    let proto = header.protos.1 + proto * 12;
+    let mut parameters = String::new();
+    let list = read(proto + 8, 4)?;
+    if list != 0 {
+        for parameter in 0..read(list, 4)? {
+            parameters.push_str(&typeName(data, header, read(list + 4 + parameter * 2, 2)?)?);
+        }
+    }
+    return Some(format!("{}->{}({}){}", typeName(data, header, class)?, stringData(data, header, name)?.2, parameters, typeName(data, header, read(proto + 4, 4)?)?));
+}
+
+fn instructionSize(data: &[u8], instruction: u64) -> Option<u64> { //in 16 bit code units
+    let unit = readUInt(data, instruction, 2, false)?;
+    let read = |index: u64, size: usize| readUInt(data, instruction + index * 2, size, false);
+    return Some(match unit & 0xff {
+        0x00 => match unit { //nop, or one of the payloads
+            PACKED_SWITCH_PAYLOAD => 4 + read(1, 2)? * 2,
+            SPARSE_SWITCH_PAYLOAD => 2 + read(1, 2)? * 4,
+            FILL_ARRAY_DATA_PAYLOAD => 4 + (read(1, 2)? * read(2, 4)? + 1) / 2,
+            _ => 1,
+        },
+        0x02 | 0x05 | 0x08 | 0x13 | 0x15 | 0x16 | 0x19 | 0x1a | 0x1c | 0x1f | 0x20 | 0x22 | 0x23 | 0x29 => 2,
+        0x03 | 0x06 | 0x09 | 0x14 | 0x17 | 0x1b | 0x24 | 0x25 | 0x26 | 0x2a | 0x2b | 0x2c => 3,
+        0x18 => 5,
+        0x2d..=0x3d | 0x44..=0x6d | 0x90..=0xaf | 0xd0..=0xe2 | 0xfe | 0xff => 2,
+        0x6e..=0x72 | 0x74..=0x78 | 0xfc | 0xfd => 3,
+        0xfa | 0xfb => 4,
+        _ => 1,
+    });
+}
+
+fn references(data: &[u8], header: &Header) -> HashMap<u64, Vec<String>> { //the methods each string is loaded by (with const-string), by the index of the string
+    let mut references: HashMap<u64, Vec<String>> = HashMap::new();
+    'classes: for class in 0..header.classes.0 {
+        let mut offset = match readUInt(data, header.classes.1 + class * 32 + 24, 4, false) {
+            Some(offset) if offset != 0 => offset,
+            _ => continue,
+        };
+        let counts: Vec<u64> = (0..4).filter_map(|_| readUleb128(data, &mut offset)).collect(); //static fields, instance fields, direct methods, virtual methods
+        if counts.len() < 4 {
+            continue;
+        }
+        let fields = match counts[0].checked_add(counts[1]).and_then(|fields| fields.checked_mul(2)) { //each field is its index and its access flags
+            Some(fields) if fields <= (data.len() as u64).saturating_sub(offset) => fields, //at least a byte each
+            _ => continue,
+        };
+        for _ in 0..fields {
+            if readUleb128(data, &mut offset).is_none() {
+                continue 'classes;
+            }
+        }
+        for &count in counts[2..].iter() {
+            let mut method: u64 = 0; //indexes are given as the difference from the one before, in each list
+            for _ in 0..count {
+                let entry = (readUleb128(data, &mut offset), readUleb128(data, &mut offset), readUleb128(data, &mut offset));
+                let code = match entry {
+                    (Some(difference), Some(_), Some(code)) => match method.checked_add(difference) {
+                        Some(next) => { method = next; code },
+                        None => break,
+                    },
+                    _ => break,
+                };
+                if code == 0 || code > data.len() as u64 { //abstract or native, or not in the file
+                    continue;
+                }
+                let units = readUInt(data, code + 12, 4, false).unwrap_or(0);
+                let mut instruction = code + 16;
+                let end = cmp::min(code + 16 + units * 2, data.len() as u64);
+                while instruction < end {
+                    let string = match readUInt(data, instruction, 1, false) {
+                        Some(CONST_STRING) => readUInt(data, instruction + 2, 2, false),
+                        Some(CONST_STRING_JUMBO) => readUInt(data, instruction + 2, 4, false),
+                        _ => None,
+                    };
+                    if let (Some(string), Some(name)) = (string, methodName(data, header, method)) {
+                        let methods = references.entry(string).or_insert_with(Vec::new);
+                        if !methods.contains(&name) {
+                            methods.push(name);
+                        }
+                    }
+                    instruction += match instructionSize(data, instruction) {
+                        Some(size) => size * 2,
+                        None => break,
+                    };
+                }
+            }
+        }
+    }
+    return references;
+}
+
+/// Decodes the strings of an Android DEX file from its string table (`string_ids`): the names of classes, methods and
+/// fields, their types, and the string literals. With `withReferences` each string loaded by code is tagged with the
+/// methods that load it (like `Lcom/example/Hello;->main([Ljava/lang/String;)V`, separated by ", "). Returns the strings
+/// with at least minLength characters sorted by offset, or None if data isn't a DEX file.
+pub fn dexStrings(data: &[u8], minLength: usize, withReferences: bool) -> Option<Vec<FoundString>> {
+    let header = parseHeader(data)?;
+    let references = if withReferences { references(data, &header) } else { HashMap::new() };
+    let mut strings: Vec<FoundString> = Vec::new();
+    for index in 0..header.strings.0 {
+        let (offset, length, text) = match stringData(data, &header, index) {
+            Some(string) if string.2.chars().count() >= minLength && string.1 > 0 => string,
+            _ => continue,
+        };
+        strings.push(FoundString {
+            offset: offset,
+            length: length,
+            encoding: Encoding::SevenBit,
+            text: text,
+            section: references.get(&index).map(|methods| methods.join(", ")),
+            address: None,
+            member: None,
+            stream: None,
+        });
+    }
+    strings.sort_by_key(|found| found.offset);
+    return Some(strings);
+}
+
+#[cfg(test)]
+mod tests {
+    use sections::readUInt;
+    use testing::readFile;
+    use super::dexStrings;
+
+    #[test]
+    fn testDexStrings() {
+        let data = readFile("./testCases/hello.dex");
+        let strings = dexStrings(&data, 4, false).unwrap();
+        let texts: Vec<&str> = strings.iter().map(|found| &found.text[..]).collect();
+        assert_eq!(vec!["Hello from DEX", "LHello;", "Ljava/lang/Object;", "main", "Unused string", "Café", "helper", "(LHello;)V"], texts);
+        assert_eq!((0x131, 14, None), (strings[0].offset, strings[0].length, strings[0].section.clone()));
+        let strings = dexStrings(&data, 4, true).unwrap();
+        let tagged: Vec<(&str, &str)> = strings.iter().filter_map(|found| found.section.as_ref().map(|section| (&found.text[..], &section[..]))).collect();
+        assert_eq!(vec![("Hello from DEX", "LHello;->main()V, LHello;->helper()V"), ("Café", "LHello;->main()V")], tagged);
+        let classData = readUInt(&data, readUInt(&data, 0x64, 4, false).unwrap() + 24, 4, false).unwrap() as usize;
+        for count in [&b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x01"[..], &b"\x80\x80\x80\x80\x80\x20"[..]].iter() { //2^63 and 2^40 static fields
+            let mut patched = data.clone();
+            patched[classData..classData + count.len()].copy_from_slice(count);
+            assert_eq!(texts.len(), dexStrings(&patched, 4, true).unwrap().len());
+        }
+        assert_eq!(None, dexStrings(b"dey\n035\0", 4, false));
+    }
+}
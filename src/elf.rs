@@ -0,0 +1,110 @@
+use sections::{Section, readUInt, readCString};
+
+const SHT_NULL: u64 = 0;
+const SHT_PROGBITS: u64 = 1;
+const SHT_NOBITS: u64 = 8;     //takes up no space in the file, like .bss
+const SHF_ALLOC: u64 = 0x2;    //loaded into memory
+const SHF_EXECINSTR: u64 = 0x4;
+const SHN_XINDEX: u64 = 0xffff; //the index of the section names is too big for the header and is in the first section instead
+
+struct Layout { //where the fields we need are, which differs between 32 and 64 bit files
+    shoff: u64, shentsize: u64, shnum: u64, shstrndx: u64, //in the file header
+    flags: u64, addr: u64, offset: u64, size: u64, link: u64, //in each section header
+    word: usize, //the size of the fields that are 32 or 64 bits
+}
+
+const ELF32: Layout = Layout { shoff: 0x20, shentsize: 0x2E, shnum: 0x30, shstrndx: 0x32, flags: 0x08, addr: 0x0C, offset: 0x10, size: 0x14, link: 0x18, word: 4 };
+const ELF64: Layout = Layout { shoff: 0x28, shentsize: 0x3A, shnum: 0x3C, shstrndx: 0x3E, flags: 0x08, addr: 0x10, offset: 0x18, size: 0x20, link: 0x28, word: 8 };
+
+pub fn isElf(data: &[u8]) -> bool {
+    return data.len() >= 6 && &data[0..4] == b"\x7fELF";
+}
+
+/// Parses the section headers of an ELF file (32 or 64 bit, either byte order). Returns None if data isn't an ELF file
+/// or has no section headers.
+pub fn parseSections(data: &[u8]) -> Option<Vec<Section>> {
+    if !isElf(data) {
+        return None;
+    }
+    let layout = match data[4] {
+        1 => ELF32,
+        2 => ELF64,
+        _ => return None,
+    };
+    let bigEndian = match data[5] {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+    let read = |offset: u64, size: usize| readUInt(data, offset, size, bigEndian);
+    let shoff = read(layout.shoff, layout.word)?;
+    let shentsize = read(layout.shentsize, 2)?;
+    let mut shnum = read(layout.shnum, 2)?;
+    let mut shstrndx = read(layout.shstrndx, 2)?;
+    if shoff == 0 || shentsize == 0 {
+        return None;
+    }
+    if shnum == 0 { //too many sections for the header, the real count is the size of the first section
+        shnum = read(shoff.checked_add(layout.size)?, layout.word)?;
+    }
+    if shstrndx == SHN_XINDEX {
+        shstrndx = read(shoff.checked_add(layout.link)?, 4)?;
+    }
+    if shnum == 0 || shnum.checked_mul(shentsize).and_then(|size| size.checked_add(shoff)).is_none_or(|end| end > data.len() as u64) {
+        return None;
+    }
+    let names = shstrndx.checked_mul(shentsize).and_then(|header| read(shoff + header + layout.offset, layout.word)); //where the section names start, if there are any
+    let mut sections: Vec<Section> = Vec::new();
+    for index in 1..shnum { //the first section is always empty
+        let header = shoff + index * shentsize;
+        let sectionType = read(header + 4, 4)?;
+        let flags = read(header + layout.flags, layout.word)?;
+        let address = read(header + layout.addr, layout.word)?;
+        let offset = read(header + layout.offset, layout.word)?;
+        let size = read(header + layout.size, layout.word)?;
+        if sectionType == SHT_NULL || sectionType == SHT_NOBITS || offset.checked_add(size).is_none_or(|end| end > data.len() as u64) { //doesn't fit in the file
+            continue;
+        }
+        let name = match names {
+            Some(names) => names.checked_add(read(header, 4)?).and_then(|name| readCString(data, name)).unwrap_or_default(),
+            None => String::new(),
+        };
+        let isLoaded = flags & SHF_ALLOC != 0;
+        let isData = flags & SHF_EXECINSTR == 0 && (isLoaded || (sectionType == SHT_PROGBITS && !name.starts_with(".debug") && !name.starts_with(".zdebug"))); //loaded data, plus the likes of .comment
+        sections.push(Section {
+            name: name,
+            offset: offset,
+            size: size,
+            address: if isLoaded { Some(address) } else { None },
+            imageBase: 0,
+            isData: isData,
+        });
+    }
+    return Some(sections);
+}
+
+#[cfg(test)]
+mod tests {
+    use sections::readUInt;
+    use testing::readFile;
+    use super::parseSections;
+
+    #[test]
+    fn testParseSections() {
+        let data = readFile("./testCases/a.out");
+        let sections = parseSections(&data).unwrap();
+        let names: Vec<&str> = sections.iter().filter(|section| section.isData).map(|section| &section.name[..]).collect();
+        assert!(names.contains(&".rodata") && names.contains(&".data") && names.contains(&".comment") && names.contains(&".dynstr"));
+        assert!(!names.contains(&".text") && !names.contains(&".bss") && !names.contains(&".strtab"));
+        let rodata = sections.iter().find(|section| section.name == ".rodata").unwrap();
+        assert_eq!((2400, 17, Some(0x400960)), (rodata.offset, rodata.size, rodata.address));
+        let header = sections.iter().position(|section| section.name == ".rodata").unwrap() as u64; //no empty or .bss sections come before it
+        let mut patched = data.clone();
+        let offset = (readUInt(&data, 0x28, 8, false).unwrap() + (header + 1) * 64 + 0x18) as usize;
+        patched[offset..offset + 8].copy_from_slice(b"\xf0\xff\xff\xff\xff\xff\xff\xff"); //runs past the end of the file
+        assert!(parseSections(&patched).unwrap().iter().all(|section| section.name != ".rodata"));
+
+        assert_eq!(None, parseSections(&data[0..0x30])); //the section headers are past the end
+        assert_eq!(None, parseSections(b"MZ\x90\0"));
+    }
+}
@@ -0,0 +1,179 @@
+use std::str; //to read utf-8
+
+/// The character encodings that strings can be searched for in. The single letter names are the ones used by GNU strings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    SevenBit,           //s: ASCII (or UTF-8 when unicode support is enabled)
+    EightBit,           //S: ASCII plus the high bytes, decoded as Latin-1
+    BigEndian16,        //b: UTF-16BE
+    LittleEndian16,     //l: UTF-16LE
+    BigEndian32,        //B: UTF-32BE
+    LittleEndian32,     //L: UTF-32LE
+}
+
+impl Encoding {
+    /// Accepts both the GNU strings letters and a descriptive name (e.g. "l" or "utf16le").
+    pub fn fromName(name: &str) -> Option<Encoding> {
+        return match name {
+            "s" | "ascii" | "utf8" => Some(Encoding::SevenBit),
+            "S" | "latin1" => Some(Encoding::EightBit),
+            "b" | "utf16be" => Some(Encoding::BigEndian16),
+            "l" | "utf16le" => Some(Encoding::LittleEndian16),
+            "B" | "utf32be" => Some(Encoding::BigEndian32),
+            "L" | "utf32le" => Some(Encoding::LittleEndian32),
+            _ => None,
+        };
+    }
+
+    /// The descriptive name of the encoding, used to tag strings when searching for more than one encoding.
+    pub fn name(&self) -> &'static str {
+        return match *self {
+            Encoding::SevenBit => "ascii",
+            Encoding::EightBit => "latin1",
+            Encoding::BigEndian16 => "utf16be",
+            Encoding::LittleEndian16 => "utf16le",
+            Encoding::BigEndian32 => "utf32be",
+            Encoding::LittleEndian32 => "utf32le",
+        };
+    }
+
+    /// The size (in bytes) of one code unit.
+    pub fn width(&self) -> usize {
+        return match *self {
+            Encoding::SevenBit | Encoding::EightBit => 1,
+            Encoding::BigEndian16 | Encoding::LittleEndian16 => 2,
+            Encoding::BigEndian32 | Encoding::LittleEndian32 => 4,
+        };
+    }
+
+    pub fn isBigEndian(&self) -> bool {
+        return *self == Encoding::BigEndian16 || *self == Encoding::BigEndian32;
+    }
+}
+
+/// Parses a comma separated list of encodings such as "s,l". Duplicates are removed, the order is kept.
+pub fn parseEncodings(names: &str) -> Result<Vec<Encoding>, String> {
+    let mut encodings: Vec<Encoding> = Vec::new();
+    for name in names.split(',') {
+        let encoding = match Encoding::fromName(name.trim()) {
+            Some(encoding) => encoding,
+            None => return Err(format!("Unknown encoding {}, valid encodings are s, S, b, l, B and L. ", name)),
+        };
+        if !encodings.contains(&encoding) {
+            encodings.push(encoding);
+        }
+    }
+    return Ok(encodings);
+}
+
+pub fn isPrintableASCII(char: u8) -> bool { //checks whether the character (as a u8) is printable
+    if char >= 32u8 && char <= 126u8 {  //printable is 32..126
+        return true;
+    }
+    return false;
+}
+
+pub fn isUTF8(file: &[u8], index: usize) -> (bool, usize) {   //checks if the group of non-printable characters at index in file is a valid unicode character
+    if index+3 >= file.len(){ //check to see if we are going to search out of bounds
+        return (false, 0);
+    }
+    let mut foundUTF8 = false;  //used to hold the current status of whether or not we found a utf-8 character
+    let mut len = 0;            //used to hold the length (in bytes) of the utf-8 character
+    let buf = &[file[index], file[index+1], file[index+2], file[index+3]];
+    for i in 1..4 { //1,2,3
+        if str::from_utf8(&buf[0..i]).is_err() { //keep track of whether or not we found a UTF-8 char
+            if foundUTF8 == false {
+                foundUTF8 = true;
+                len = i+1;
+            }
+        }
+    }
+    return (foundUTF8, len);
+}
+
+pub fn readUnit(file: &[u8], index: usize, encoding: Encoding) -> Option<u32> { //reads the code unit at index, None if it would read past the end of the file
+    let width = encoding.width();
+    if index+width > file.len() {
+        return None;
+    }
+    let mut unit: u32 = 0;
+    for i in 0..width {
+        let byte = if encoding.isBigEndian() { file[index+i] } else { file[index+width-1-i] };
+        unit = unit << 8 | byte as u32;
+    }
+    return Some(unit);
+}
+
+pub fn isPrintableUnit(file: &[u8], index: usize, unicode: bool, encoding: Encoding) -> (bool, usize) { //checks whether the character at index is printable, returns the number of bytes it takes up
+    let width = encoding.width();
+    let unit = match readUnit(file, index, encoding) {
+        Some(unit) => unit,
+        None => return (false, 0),
+    };
+    if unit < 128 {
+        return (isPrintableASCII(unit as u8), width);
+    }
+    if unit < 0xA0 { //0x80..0x9F are control characters
+        return (false, 0);
+    }
+    if encoding == Encoding::EightBit { //the high half of Latin-1 is always allowed in 8-bit mode
+        return (true, width);
+    }
+    if !unicode {
+        return (false, 0);
+    }
+    if width == 2 && unit >= 0xD800 && unit <= 0xDBFF { //high surrogate, must be followed by a low surrogate
+        return match readUnit(file, index+2, encoding) {
+            Some(low) if low >= 0xDC00 && low <= 0xDFFF => (true, 4),
+            _ => (false, 0),
+        };
+    }
+    if ::std::char::from_u32(unit).is_none() { //lone surrogates and anything past U+10FFFF
+        return (false, 0);
+    }
+    return (true, width);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoding;
+    use super::parseEncodings;
+    use super::isUTF8;
+    use super::isPrintableASCII;
+    use super::readUnit;
+
+    #[test]
+    fn testIsUTF8() {
+        let vec = vec![62u8, 194u8, 162u8, 62u8];
+        assert_eq!((true, 3), isUTF8(&vec, 0));
+    }
+
+    #[test]
+    fn testIsNotUTF8() {
+        let vec = vec![62u8, 62u8, 62u8, 62u8];
+        assert_eq!((false, 0), isUTF8(&vec, 0));
+    }
+
+    #[test]
+    fn testIsASCII() {
+        assert_eq!(true, isPrintableASCII(97u8));
+        assert_eq!(false, isPrintableASCII(10u8));
+    }
+
+    #[test]
+    fn testReadUnit() {
+        let vec = vec![1u8, 2u8, 3u8, 4u8];
+        assert_eq!(Some(0x0201), readUnit(&vec, 0, Encoding::LittleEndian16));
+        assert_eq!(Some(0x0102), readUnit(&vec, 0, Encoding::BigEndian16));
+        assert_eq!(Some(0x04030201), readUnit(&vec, 0, Encoding::LittleEndian32));
+        assert_eq!(None, readUnit(&vec, 1, Encoding::BigEndian32));
+    }
+
+    #[test]
+    fn testParseEncodings() {
+        assert_eq!(Ok(vec![Encoding::SevenBit]), parseEncodings("s"));
+        assert_eq!(Ok(vec![Encoding::SevenBit, Encoding::LittleEndian16, Encoding::BigEndian32]), parseEncodings("s,utf16le,B"));
+        assert_eq!(Ok(vec![Encoding::EightBit]), parseEncodings("S,latin1"));
+        assert!(parseEncodings("s,x").is_err());
+    }
+}
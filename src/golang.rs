@@ -0,0 +1,118 @@
+use scanner::{ScanOptions, FoundString};
+use sections::{Section, readUInt, readUleb128, readUtf8, addressToOffset, pointedStrings, MAX_POINTED};
+
+const BUILDINFO_MAGIC: &'static [u8] = b"\xff Go buildinf:";
+const MODINFO_START: &'static [u8] = b"0w\xaf\x0c\x92t\x08\x02A\xe1\xc1\x07\xe6\xd6\x18\xe6"; //the module info is wrapped in these
+const MODINFO_END: &'static [u8] = b"\xf92C1\x86\x18 r\x00\x82B\x10A\x16\xd8\xf2";
+const PCLNTAB_MAGICS: [u64; 4] = [0xfffffffb, 0xfffffffa, 0xfffffff0, 0xfffffff1]; //Go 1.2, 1.16, 1.18 and 1.20
+
+struct Layout { //how the binary stores pointers
+    ptrSize: usize,
+    bigEndian: bool,
+}
+
+fn findBuildInfo(data: &[u8]) -> Option<u64> { //the offset of the build info, which is aligned to 16 bytes in memory
+    return data.windows(BUILDINFO_MAGIC.len()).position(|window| window == BUILDINFO_MAGIC).map(|offset| offset as u64);
+}
+
+fn findLayout(data: &[u8], sections: &[Section], buildInfo: Option<u64>) -> Option<Layout> { //from the build info, or the header of the pclntab (the table of function names and line numbers)
+    if let Some(offset) = buildInfo {
+        let ptrSize = readUInt(data, offset + 14, 1, false)? as usize;
+        let flags = readUInt(data, offset + 15, 1, false)?;
+        if ptrSize == 4 || ptrSize == 8 {
+            return Some(Layout { ptrSize: ptrSize, bigEndian: flags & 1 != 0 });
+        }
+    }
+    let pclntab = sections.iter().find(|section| section.name.ends_with("gopclntab"))?; //.gopclntab in ELF, __gopclntab in Mach-O
+    let bigEndian = match readUInt(data, pclntab.offset, 4, false)? {
+        magic if PCLNTAB_MAGICS.contains(&magic) => false,
+        _ if PCLNTAB_MAGICS.contains(&readUInt(data, pclntab.offset, 4, true)?) => true,
+        _ => return None,
+    };
+    let ptrSize = readUInt(data, pclntab.offset + 7, 1, false)? as usize;
+    if ptrSize != 4 && ptrSize != 8 {
+        return None;
+    }
+    return Some(Layout { ptrSize: ptrSize, bigEndian: bigEndian });
+}
+
+fn buildInfoStrings(data: &[u8], sections: &[Section], offset: u64, layout: &Layout, strings: &mut Vec<FoundString>) { //the Go version and the lines of the module info (the path, main module, dependencies and build settings)
+    let mut found: Vec<(u64, u64)> = Vec::new(); //the offsets and lengths of the version and module info
+    let flags = readUInt(data, offset + 15, 1, false).unwrap_or(0);
+    if flags & 2 != 0 { //since Go 1.18 the strings follow the header, prefixed by their lengths
+        let mut position = offset + 32;
+        for _ in 0..2 {
+            let length = match readUleb128(data, &mut position) {
+                Some(length) if length <= MAX_POINTED => length,
+                _ => break,
+            };
+            found.push((position, length));
+            position += length;
+        }
+    }
+    else { //before that the header points at the string headers
+        let read = |offset: u64| readUInt(data, offset, layout.ptrSize, layout.bigEndian);
+        for index in 0..2 {
+            let header = read(offset + 16 + index * layout.ptrSize as u64).and_then(|address| addressToOffset(sections, address));
+            let string = header.and_then(|header| Some((addressToOffset(sections, read(header)?)?, read(header + layout.ptrSize as u64)?)));
+            found.extend(string);
+        }
+    }
+    for (mut start, length) in found {
+        if length > MAX_POINTED || start + length > data.len() as u64 {
+            continue;
+        }
+        let mut bytes = &data[start as usize..(start + length) as usize];
+        if bytes.len() >= MODINFO_START.len() + MODINFO_END.len() && bytes.starts_with(MODINFO_START) && bytes.ends_with(MODINFO_END) {
+            start += MODINFO_START.len() as u64;
+            bytes = &bytes[MODINFO_START.len()..bytes.len() - MODINFO_END.len()];
+        }
+        for line in bytes.split(|byte| *byte == b'\n') { //one string per line
+            strings.extend(readUtf8(data, start, line.len() as u64));
+            start += line.len() as u64 + 1;
+        }
+    }
+}
+
+/// Finds the strings of a Go binary: the version and module info from the build info, and the string literals (which Go
+/// stores one after the other without terminators) split at the lengths given by the string headers (pointer and length
+/// pairs) in the data sections. Literals that are only referenced from code aren't found. Returns None if data isn't a
+/// Go binary, otherwise the strings with at least the minimum length, sorted by offset.
+pub fn goStrings(data: &[u8], sections: &[Section], options: &ScanOptions) -> Option<Vec<FoundString>> {
+    let buildInfo = findBuildInfo(data);
+    let layout = findLayout(data, sections, buildInfo)?;
+    let mut strings: Vec<FoundString> = Vec::new();
+    if let Some(offset) = buildInfo {
+        buildInfoStrings(data, sections, offset, &layout, &mut strings);
+    }
+    strings.extend(pointedStrings(data, sections, layout.ptrSize, layout.bigEndian));
+    strings.retain(|found| found.text.chars().count() >= options.getMinLength());
+    strings.sort_by_key(|found| (found.offset, found.length));
+    strings.dedup_by_key(|found| (found.offset, found.length));
+    return Some(strings);
+}
+
+#[cfg(test)]
+mod tests {
+    use scanner::ScanOptions;
+    use sections::findSections;
+    use testing::readFile;
+    use super::{goStrings, findBuildInfo};
+
+    #[test]
+    fn testGoStrings() {
+        let data = readFile("./testCases/gohello");
+        let strings = goStrings(&data, &findSections(&data).unwrap(), &ScanOptions::new()).unwrap();
+        let texts: Vec<&str> = strings.iter().map(|found| &found.text[..]).collect();
+        assert_eq!(vec!["hello, world", "Go is fun", "net/http", "unexpected EOF", "go1.21.0", "path\texample.com/hello", "mod\texample.com/hello\t(devel)\t",
+            "build\t-compiler=gc", "build\tGOOS=linux"], texts);
+        assert_eq!((0x2000 + 12, 9), (strings[1].offset, strings[1].length));
+        let mut patched = data.clone(); //a build info string length that overflows when added to its offset
+        let lengthOffset = findBuildInfo(&patched).unwrap() as usize + 32;
+        patched[lengthOffset..lengthOffset+10].copy_from_slice(b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01");
+        let strings = goStrings(&patched, &findSections(&patched).unwrap(), &ScanOptions::new()).unwrap();
+        let texts: Vec<&str> = strings.iter().map(|found| &found.text[..]).collect();
+        assert_eq!(vec!["hello, world", "Go is fun", "net/http", "unexpected EOF"], texts);
+        assert_eq!(None, goStrings(&readFile("./testCases/a.out"), &findSections(&readFile("./testCases/a.out")).unwrap(), &ScanOptions::new()));
+    }
+}
@@ -0,0 +1,144 @@
+use encoding::Encoding;
+use scanner::FoundString;
+use sections::readUInt;
+
+const CLASS_MAGIC: u64 = 0xcafebabe;
+const MIN_MAJOR_VERSION: u64 = 45; //Java 1.0, which tells classes from fat Mach-O files (which have the number of slices there)
+const CONSTANT_UTF8: u64 = 1;
+const CONSTANT_CLASS: u64 = 7;
+const CONSTANT_STRING: u64 = 8;
+
+pub fn isClass(data: &[u8]) -> bool {
+    return readUInt(data, 0, 4, true) == Some(CLASS_MAGIC) && readUInt(data, 6, 2, true).is_some_and(|major| major >= MIN_MAJOR_VERSION);
+}
+
+fn constantSize(tag: u64) -> Option<u64> { //the size of a constant pool entry after its tag, other than a Utf8 entry
+    return match tag {
+        3 | 4 => Some(4),                   //Integer, Float
+        5 | 6 => Some(8),                   //Long, Double
+        7 | 8 | 16 | 19 | 20 => Some(2),    //Class, String, MethodType, Module, Package
+        9 | 10 | 11 | 12 | 17 | 18 => Some(4), //the references, NameAndType, Dynamic, InvokeDynamic
+        15 => Some(3),                      //MethodHandle
+        _ => None,
+    };
+}
+
+/// Decodes Java's modified UTF-8, in which null is written as two bytes and characters outside the BMP as the two
+/// halves of their UTF-16 surrogate pair. None if the bytes aren't modified UTF-8.
+pub fn decodeModifiedUtf8(bytes: &[u8]) -> Option<String> {
+    let mut units: Vec<u16> = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index] as u16;
+        let continuation = |offset: usize| bytes.get(index + offset).map(|byte| *byte as u16).filter(|byte| byte & 0xc0 == 0x80);
+        if byte & 0x80 == 0 && byte != 0 {
+            units.push(byte);
+            index += 1;
+        }
+        else if byte & 0xe0 == 0xc0 {
+            units.push((byte & 0x1f) << 6 | continuation(1)? & 0x3f);
+            index += 2;
+        }
+        else if byte & 0xf0 == 0xe0 {
+            units.push((byte & 0x0f) << 12 | (continuation(1)? & 0x3f) << 6 | continuation(2)? & 0x3f);
+            index += 3;
+        }
+        else {
+            return None;
+        }
+    }
+    return String::from_utf16(&units).ok();
+}
+
+/// Decodes the `CONSTANT_Utf8` entries of the constant pool of a class file (the names of classes, methods and fields,
+/// their types, and the string literals), tagging each with the name of the class, plus `:string` for those used as
+/// `CONSTANT_String` literals. Returns the strings with at least minLength characters sorted by offset, or None if data
+/// isn't a class file.
+pub fn classStrings(data: &[u8], minLength: usize) -> Option<Vec<FoundString>> {
+    if !isClass(data) {
+        return None;
+    }
+    let read = |offset: u64, size: usize| readUInt(data, offset, size, true);
+    let count = read(8, 2)?;
+    let mut utf8: Vec<Option<(u64, u64)>> = vec![None; count as usize]; //the offset and length of each Utf8 entry, by index
+    let mut classes: Vec<Option<u64>> = vec![None; count as usize]; //the Utf8 entry naming each Class entry
+    let mut literals: Vec<bool> = vec![false; count as usize]; //whether each Utf8 entry is used by a String entry
+    let mut entry = 10;
+    let mut index = 1;
+    while index < count {
+        let tag = read(entry, 1)?;
+        if tag == CONSTANT_UTF8 {
+            let length = read(entry + 1, 2)?;
+            utf8[index as usize] = Some((entry + 3, length));
+            entry += 3 + length;
+        }
+        else {
+            let reference = read(entry + 1, 2)?;
+            if tag == CONSTANT_CLASS {
+                classes[index as usize] = Some(reference);
+            }
+            if tag == CONSTANT_STRING && reference < count {
+                literals[reference as usize] = true;
+            }
+            entry += 1 + constantSize(tag)?;
+        }
+        index += if tag == 5 || tag == 6 { 2 } else { 1 }; //Longs and Doubles take up two entries
+    }
+    let thisClass = read(entry + 2, 2)?;
+    let name = classes.get(thisClass as usize).cloned().unwrap_or(None)
+        .and_then(|name| utf8.get(name as usize).cloned().unwrap_or(None))
+        .and_then(|(offset, length)| decodeModifiedUtf8(&data[offset as usize..(offset + length) as usize]))
+        .unwrap_or_default();
+    let mut strings: Vec<FoundString> = Vec::new();
+    for (index, entry) in utf8.iter().enumerate() {
+        let (offset, length) = match *entry {
+            Some(entry) if entry.0 + entry.1 <= data.len() as u64 => entry,
+            _ => continue,
+        };
+        let text = match decodeModifiedUtf8(&data[offset as usize..(offset + length) as usize]) {
+            Some(text) => text,
+            None => continue,
+        };
+        if text.chars().count() < minLength || text.len() == 0 {
+            continue;
+        }
+        strings.push(FoundString {
+            offset: offset,
+            length: length as usize,
+            encoding: Encoding::SevenBit,
+            text: text,
+            section: Some(if literals[index] { format!("{}:string", name) } else { name.clone() }),
+            address: None,
+            member: None,
+            stream: None,
+        });
+    }
+    return Some(strings);
+}
+
+#[cfg(test)]
+mod tests {
+    use testing::readFile;
+    use super::{classStrings, decodeModifiedUtf8};
+
+    #[test]
+    fn testDecodeModifiedUtf8() {
+        assert_eq!(Some(String::from("a\0b")), decodeModifiedUtf8(b"a\xc0\x80b"));
+        assert_eq!(Some(String::from("\u{1f600}")), decodeModifiedUtf8(b"\xed\xa0\xbd\xed\xb8\x80")); //a surrogate pair
+        assert_eq!(None, decodeModifiedUtf8(b"a\0b"));
+        assert_eq!(None, decodeModifiedUtf8(b"\xf0\x9f\x98\x80")); //the 4 byte form of standard UTF-8
+    }
+
+    #[test]
+    fn testClassStrings() {
+        let data = readFile("./testCases/Hello.class");
+        let strings = classStrings(&data, 4).unwrap();
+        let found: Vec<(&str, &str)> = strings.iter().map(|found| (found.section.as_ref().unwrap().as_ref(), found.text.as_ref())).collect();
+        assert!(found.contains(&("com/example/Hello", "java/lang/Object")));
+        assert!(found.contains(&("com/example/Hello", "GREETING")));
+        assert!(found.contains(&("com/example/Hello:string", "Hello from Java")));
+        assert!(found.contains(&("com/example/Hello:string", "Café \u{1f600} nul\0byte")));
+        assert!(!found.iter().any(|&(_, text)| text == "out")); //too short
+        assert_eq!(None, classStrings(b"\xca\xfe\xba\xbe\0\0\0\x02", 4)); //a fat Mach-O file
+    }
+}
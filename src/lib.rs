@@ -0,0 +1,60 @@
+//! RStrings finds the printable strings in binary data, like the unix strings command.
+//!
+//! ```
+//! use rstrings::{ScanOptions, Scanner};
+//!
+//! let scanner = Scanner::new(ScanOptions::new().minLength(4));
+//! for found in scanner.scan(b"\x7fELF\0\x01hello world\0") {
+//!     println!("{}:{}", found.offset, found.text);
+//! }
+//! ```
+#![allow(non_snake_case)]
+
+extern crate glob;
+extern crate walkdir;
+extern crate rustc_serialize;
+extern crate rustc_demangle;
+extern crate cpp_demangle;
+extern crate flate2;
+extern crate xz2;
+extern crate bzip2;
+extern crate zstd;
+
+mod encoding;
+mod scanner;
+mod walk;
+mod output;
+mod sections;
+mod elf;
+mod pe;
+mod macho;
+mod demangle;
+mod golang;
+mod rust;
+mod zip;
+mod java;
+mod dex;
+mod wasm;
+mod compression;
+mod archive;
+mod pdf;
+
+pub use encoding::{Encoding, parseEncodings};
+pub use scanner::{ScanOptions, Scanner, FoundString, Strings, ReaderStrings};
+pub use walk::FileWalker;
+pub use sections::{Section, SectionFilter, findSections, scanSections};
+pub use demangle::demangle;
+pub use compression::{Compression, decompress, decompressReader, scanDecompressed};
+pub use output::{Format, Column, OffsetFormat, toJson, parseColumns, toDelimited, toDelimitedHeader};
+
+#[cfg(test)]
+mod testing { //shared by the tests of the modules
+    use std::fs::File;
+    use std::io::Read;
+
+    pub fn readFile(path: &str) -> Vec<u8> {
+        let mut data: Vec<u8> = Vec::new();
+        File::open(path).unwrap().read_to_end(&mut data).unwrap();
+        return data;
+    }
+}
@@ -0,0 +1,153 @@
+use sections::{Section, readUInt};
+
+const LC_SEGMENT: u64 = 0x1;
+const LC_SEGMENT_64: u64 = 0x19;
+const SECTION_TYPE: u64 = 0xff;
+const S_ZEROFILL: u64 = 0x1;               //takes up no space in the file, like __bss
+const S_GB_ZEROFILL: u64 = 0xc;
+const S_THREAD_LOCAL_ZEROFILL: u64 = 0x12;
+const S_ATTR_PURE_INSTRUCTIONS: u64 = 0x80000000;
+const S_ATTR_SOME_INSTRUCTIONS: u64 = 0x400;
+const MAX_FAT_ARCHS: u64 = 20; //fat files and Java classes share a magic number, classes have a version of at least 45 where fat files have the number of slices
+
+fn architecture(cpuType: u64) -> String { //the name lipo gives the architecture
+    return String::from(match cpuType {
+        7 => "i386",
+        0x01000007 => "x86_64",
+        12 => "arm",
+        0x0100000c => "arm64",
+        0x0200000c => "arm64_32",
+        18 => "ppc",
+        0x01000012 => "ppc64",
+        _ => return format!("cpu{}", cpuType),
+    });
+}
+
+/// Parses the sections of a Mach-O file, or of every slice of a universal (fat) file. Sections are named like
+/// `__TEXT,__cstring`, prefixed with the architecture of the slice for fat files (e.g. `arm64:__TEXT,__cstring`).
+/// Returns None if data isn't a Mach-O file.
+pub fn parseSections(data: &[u8]) -> Option<Vec<Section>> {
+    let magic = readUInt(data, 0, 4, true)?;
+    if magic != 0xcafebabe && magic != 0xcafebabf {
+        return parseSlice(data, 0, "");
+    }
+    let fat64 = magic == 0xcafebabf;
+    let count = readUInt(data, 4, 4, true)?;
+    if count == 0 || count > MAX_FAT_ARCHS {
+        return None;
+    }
+    let mut sections: Vec<Section> = Vec::new();
+    for index in 0..count {
+        let read = |offset: u64, size: usize| readUInt(data, offset, size, true);
+        let (cpuType, offset, size) = if fat64 {
+            let arch = 8 + index * 32;
+            (read(arch, 4)?, read(arch + 8, 8)?, read(arch + 16, 8)?)
+        }
+        else {
+            let arch = 8 + index * 20;
+            (read(arch, 4)?, read(arch + 8, 4)?, read(arch + 12, 4)?)
+        };
+        if offset.checked_add(size).is_none_or(|end| end > data.len() as u64) {
+            continue;
+        }
+        let slice = &data[offset as usize..(offset + size) as usize];
+        if let Some(found) = parseSlice(slice, offset, &format!("{}:", architecture(cpuType))) {
+            sections.extend(found);
+        }
+    }
+    return Some(sections);
+}
+
+fn parseSlice(data: &[u8], base: u64, prefix: &str) -> Option<Vec<Section>> { //a single Mach-O file starting at base in the whole file
+    let (bigEndian, is64) = match readUInt(data, 0, 4, false)? {
+        0xfeedface => (false, false),
+        0xfeedfacf => (false, true),
+        0xcefaedfe => (true, false),
+        0xcffaedfe => (true, true),
+        _ => return None,
+    };
+    let read = |offset: u64, size: usize| readUInt(data, offset, size, bigEndian);
+    let word = if is64 { 8 } else { 4 };
+    let commands = read(16, 4)?;
+    let mut command = if is64 { 32 } else { 28 };
+    let mut sections: Vec<Section> = Vec::new();
+    let mut imageBase = 0; //where __TEXT is loaded, which is where the header is
+    for _ in 0..commands {
+        let (commandType, commandSize) = (read(command, 4)?, read(command + 4, 4)?);
+        if commandSize < 8 {
+            break;
+        }
+        if commandType == LC_SEGMENT || commandType == LC_SEGMENT_64 {
+            let segmentName = readName(data, command + 8);
+            if segmentName == "__TEXT" {
+                imageBase = read(command + 24, word)?;
+            }
+            let count = read(command + 8 + 16 + word as u64 * 4 + 8, 4)?;
+            let mut header = command + if is64 { 72 } else { 56 };
+            for _ in 0..count {
+                let flags = read(header + 16 + 16 + word as u64 * 2 + 4 * 4, 4)?;
+                let offset = read(header + 16 + 16 + word as u64 * 2, 4)?;
+                let sectionType = flags & SECTION_TYPE;
+                let hasContents = sectionType != S_ZEROFILL && sectionType != S_GB_ZEROFILL && sectionType != S_THREAD_LOCAL_ZEROFILL && offset != 0;
+                if hasContents {
+                    sections.push(Section {
+                        name: format!("{}{},{}", prefix, readName(data, header + 16), readName(data, header)),
+                        offset: base + offset,
+                        size: read(header + 32 + word as u64, word)?,
+                        address: Some(read(header + 32, word)?),
+                        imageBase: 0,
+                        isData: flags & (S_ATTR_PURE_INSTRUCTIONS | S_ATTR_SOME_INSTRUCTIONS) == 0,
+                    });
+                }
+                header += if is64 { 80 } else { 68 };
+            }
+        }
+        command += commandSize;
+    }
+    for section in sections.iter_mut() {
+        section.imageBase = imageBase;
+    }
+    return Some(sections);
+}
+
+fn readName(data: &[u8], offset: u64) -> String { //segment and section names are 16 bytes, null padded
+    if offset.checked_add(16).is_none_or(|end| end > data.len() as u64) {
+        return String::new();
+    }
+    let name = &data[offset as usize..offset as usize + 16];
+    return String::from_utf8_lossy(&name[..name.iter().position(|byte| *byte == 0).unwrap_or(16)]).into_owned();
+}
+
+#[cfg(test)]
+mod tests {
+    use testing::readFile;
+    use super::parseSections;
+
+    #[test]
+    fn testParseSections() {
+        let data = readFile("./testCases/hello.macho");
+        let sections: Vec<(String, u64, u64, Option<u64>, bool)> = parseSections(&data).unwrap().into_iter().map(|section| (section.name, section.offset, section.size, section.address, section.isData)).collect();
+        assert_eq!(vec![
+            (String::from("__TEXT,__text"), 632, 0x0a, Some(0), false),
+            (String::from("__TEXT,__cstring"), 642, 0x21, Some(0x0a), true),
+            (String::from("__TEXT,__objc_methname"), 675, 0x0f, Some(0x2b), true),
+            (String::from("__DATA,__cfstring"), 690, 0x20, Some(0x3a), true),
+            (String::from("__DATA,__data"), 722, 0x12, Some(0x5a), true),
+        ], sections);
+        assert_eq!(None, parseSections(&readFile("./testCases/a.out")));
+    }
+
+    #[test]
+    fn testParseFatSections() {
+        let data = readFile("./testCases/hello.fat");
+        let sections = parseSections(&data).unwrap();
+        let names: Vec<&str> = sections.iter().map(|section| &section.name[..]).collect();
+        assert_eq!(vec!["x86_64:__TEXT,__text", "x86_64:__TEXT,__cstring", "x86_64:__TEXT,__objc_methname", "x86_64:__DATA,__cfstring", "x86_64:__DATA,__data",
+            "arm64:__TEXT,__text", "arm64:__TEXT,__cstring", "arm64:__TEXT,__objc_methname"], names);
+        assert_eq!(48 + 642, sections[1].offset); //offsets are from the start of the fat file
+        assert_eq!(None, parseSections(b"\xca\xfe\xba\xbe\0\0\0\x34")); //a Java class
+        let mut huge = b"\xca\xfe\xba\xbf\0\0\0\x01\x01\0\0\x07\0\0\0\x03\xff\xff\xff\xff\xff\xff\xff\xf0\0\0\0\0\0\0\0\x20".to_vec(); //a slice that runs past the end of the address space
+        huge.resize(92, 0);
+        assert_eq!(Some(Vec::new()), parseSections(&huge));
+    }
+}
@@ -7,47 +7,144 @@ extern crate rustc_serialize;
 
 use std::io; //for stdin
 use std::fs::File; //for the file
-use std::io::BufReader; //buffered reader so we can handle large files
 use std::io::Read; //to read from the above file
-use std::str; //to read utf-8
+use std::path::PathBuf;
+use std::collections::HashMap;
+use std::sync::mpsc; //to send the strings found in each file back to be printed
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread; //for concurrency
 
-use std::thread; //for concurrency 
 extern crate num_cpus; //for autodetection of cpu count 
 
+extern crate memmap; //to map files into memory instead of reading them
+use memmap::Mmap;
+
+extern crate rstrings;
+use rstrings::{ScanOptions, Scanner, FoundString, FileWalker, Format, Column, OffsetFormat, SectionFilter};
+
 const USAGE: &'static str = "
-Usage: rustStrings [options] [<file>]
+Usage: rustStrings [options] [--include=<glob>...] [--exclude=<glob>...] [<file>...]
 
 Options:
     -b, --bytes=<num>  set the number of printable bytes needed for something to qualify as a string [default: 4]
-    -t, --threads=<num>  set the number of threads to use. Use 0 to automatically detect the optimal number of threads. Note if threads > 1 than the order of the strings found may not match the order of the strings in the file. [default: 1]
+    -t, --threads=<num>  set the number of threads to use. Use 0 to automatically detect the optimal number of threads. With several files each thread searches a file at a time, otherwise the file is split between the threads. Note if threads > 1 input that can't be memory mapped (like stdin) is read into memory before searching it. [default: 1]
     -n, --nullbytes  set to disable the null byte requirement
-    -f, --filename  print the name of the file before each line
+    -f, --filename  print the name of the file before each line (always on when searching more than one file, and for the files in archives, given as archive!file)
+    -R, --recursive  search the files in directories (and their subdirectories)
+    -L, --follow  follow symbolic links found while searching directories
+    --include=<glob>  only search files whose name matches the glob (e.g. *.so), can be given more than once
+    --exclude=<glob>  skip files and directories whose name matches the glob, can be given more than once
+    -a, --all  search the whole file, not just the data sections of executables and object files
+    --sections=<list>  the sections of executables and object files (ELF, PE and Mach-O, including each slice of fat files, and WebAssembly modules, whose data segments are named data[0], data[1]...) to search: data for the initialized data sections (like .rodata, .data and .comment), all, or a list of section names separated by commas. Other files are always searched from end to end. [default: data]
+    -A, --annotate  print the section each string is in and the virtual address it is loaded at (left empty when they aren't known)
+    --rva  give the addresses of strings in PE files relative to the image base (RVAs) instead of as virtual addresses
+    --resources  decode the string tables and version info in the resources of PE files (as UTF-16) instead of searching the resource section byte by byte. The strings are tagged with the resource they came from (e.g. .rsrc:string:12 or .rsrc:version:CompanyName).
+    --go  split the string literals of Go binaries (which Go runs together without null bytes) at the lengths in the string headers that point at them, and add the Go version and module info from the build info. Literals only referred to from code are still found run together.
+    --rust  split the string literals of Rust binaries (which are packed together without null bytes) at the lengths in the &str references to them, and list the places the binary can panic from as file:line:column (at the location of their Location struct). Literals only referred to from code are still found run together.
+    --dotnet  decode the #Strings heap (the names of types, methods...) and the #US heap (the string literals, as UTF-16) in the metadata of .NET assemblies, tagging each string with its heap, instead of searching them byte by byte. The heaps are decoded even though the section they are in isn't searched by default; --sections can pick them by name (e.g. --sections=#US).
+    --java  decode the constant pools of Java class files (the names of classes, methods and fields, their types and the string literals, tagged with the class, plus :string for the literals) instead of searching them. In JARs the classes are decoded and the other files are decompressed and searched; their strings are given as jar!file with offsets into the file.
+    --dex  decode the string tables of Android DEX files instead of searching them. In APKs the DEX files are decoded and the other files are decompressed and searched; their strings are given as apk!file with offsets into the file.
+    --dex-refs  with --dex tag each string that code loads with the methods that load it (e.g. Lcom/example/Main;->onCreate(Landroid/os/Bundle;)V)
+    --pdf  decode the literal and hex strings in the objects of PDFs, and in their streams once they are decompressed (including the text shown by content streams and the objects packed in object streams), tagging each string with its object (e.g. obj 12). Streams that aren't PDF syntax, like JavaScript, are searched instead. Strings from compressed streams are given with the offset of the stream plus the offset in the decompressed data.
+    --no-decompress  search gzip, zlib, xz, bzip2, zstd and lzma compressed input as it is. By default it is decompressed and what it decompresses to is searched, with locations given as the offset of the compressed stream plus the offset in the decompressed data (e.g. 0+1234).
+    --archive-depth=<num>  search the files in zip (including JAR and APK), tar, cpio and ar archives and in Debian and RPM packages as if they were separate files, giving their strings as archive!file with offsets into the file, and the archives in those up to this many levels deep. Use 0 to search archives as they are. [default: 4]
+    --max-extract=<MB>  the most megabytes decompressed from a file and the archives in it, so zip bombs can't use up the memory. Past it compressed data is searched as it is. [default: 1024]
+    --carve  also look for compressed streams (zlib, gzip, lzma, xz, bzip2 and zstd) starting anywhere in files that aren't compressed or archives as a whole, like the ones in firmware images, and search what they decompress to. Their strings are given with the offset of the stream plus the offset in the decompressed data (e.g. 4096+12). Not for stdin.
+    -d, --demangle  print C++, Rust and Swift symbol names demangled (Swift names are shortened to the names of the entity and what it is in, like main.Hello.greet)
+    --raw  with --demangle also print the mangled name, before the demangled one (left empty for strings that aren't mangled names)
     -l, --location  print the location of the string in the binary (bytes past starting point)
+    -E, --end  print the location just past the end of the string (with --location the two form a range)
+    -x, --radix=<r>  write locations in d = decimal, o = octal or x = hex, optionally followed by the number of digits to zero pad to (e.g. x8). In JSON locations that aren't plain decimal are written as strings. [default: d]
     -h, --help  display this help and exit
     -v, --version  output version information and exit
     -r, --removerepeats  set to not print strings that are repeated more than 10 times in a row. Note that this has a SMALL (1/590 trillion) chance of causing strings not to print due to hash collisions. 
-    -u, --utf8  set to enable utf-8 support
+    -u, --utf8  set to enable utf-8 support (in utf-16 mode this allows non-ASCII characters)
+    -o, --format=<fmt>  set the output format: text, json (an array of records), ndjson (one record per line), csv or tsv. Records have the file, offset, length in bytes and characters, encoding and string. [default: text]
+    -c, --columns=<list>  the columns of the csv and tsv formats, separated by commas, out of file, offset, end, length, encoding, section, address, string and demangled. By default the columns follow the flags: --filename, --location, --end, --annotate, the encoding if several are searched for, then the string (or with --demangle the demangled string, after the mangled one with --raw).
+    -e, --encoding=<enc>  select the character encoding of the strings, like GNU strings: s = 7-bit (ASCII, or UTF-8 with --utf8), S = 8-bit (Latin-1), b = 16-bit big endian, l = 16-bit little endian, B = 32-bit big endian, L = 32-bit little endian. Several encodings can be given separated by commas (e.g. s,l), in which case each string is tagged with its encoding. [default: s]
 ";
 
 #[derive(Debug, RustcDecodable)]
 struct Args {
-    arg_file: String,
+    arg_file: Vec<String>,
     flag_bytes: i32,
     flag_threads: i32,
     flag_nullbytes: bool,
     flag_filename: bool,
+    flag_all: bool,
+    flag_sections: String,
+    flag_annotate: bool,
+    flag_rva: bool,
+    flag_resources: bool,
+    flag_go: bool,
+    flag_rust: bool,
+    flag_dotnet: bool,
+    flag_java: bool,
+    flag_dex: bool,
+    flag_dex_refs: bool,
+    flag_pdf: bool,
+    flag_no_decompress: bool,
+    flag_archive_depth: usize,
+    flag_max_extract: u64,
+    flag_carve: bool,
+    flag_demangle: bool,
+    flag_raw: bool,
     flag_location: bool,
+    flag_end: bool,
+    flag_radix: String,
     flag_help: bool,
     flag_version: bool,
     flag_removerepeats: bool,
     flag_utf8: bool, 
+    flag_encoding: String,
+    flag_format: String,
+    flag_columns: String,
+    flag_recursive: bool,
+    flag_follow: bool,
+    flag_include: Vec<String>,
+    flag_exclude: Vec<String>,
 }
 
 fn main(){
     let args: Args = Docopt::new(USAGE)
         .and_then(|d| d.decode())
         .unwrap_or_else(|e| e.exit());
-    let filename = args.arg_file.clone();
+    let encodings = match rstrings::parseEncodings(&args.flag_encoding) {
+        Ok(encodings) => encodings,
+        Err(err) => panic!("{}", err),
+    };
+    let options = ScanOptions::new()
+        .minLength(args.flag_bytes as usize)
+        .nullTerminated(!args.flag_nullbytes)
+        .unicode(args.flag_utf8)
+        .encodings(encodings)
+        .resources(args.flag_resources)
+        .relativeAddresses(args.flag_rva)
+        .go(args.flag_go)
+        .rust(args.flag_rust)
+        .dotnet(args.flag_dotnet)
+        .java(args.flag_java)
+        .dex(args.flag_dex)
+        .dexReferences(args.flag_dex_refs)
+        .pdf(args.flag_pdf)
+        .decompress(!args.flag_no_decompress)
+        .archiveDepth(args.flag_archive_depth)
+        .maxExtracted(args.flag_max_extract.saturating_mul(1 << 20)) //more megabytes than fit in a u64 is no limit at all
+        .carve(args.flag_carve);
+    let format = match Format::fromName(&args.flag_format) {
+        Some(format) => format,
+        None => panic!("Unknown format {}, valid formats are text, json, ndjson, csv and tsv. ", args.flag_format),
+    };
+    let mut printer = Printer::new(&options, format, args.flag_filename, args.flag_location, args.flag_removerepeats);
+    printer.offsets = OffsetFormat::fromName(&args.flag_radix).unwrap_or_else(|err| panic!("{}", err));
+    printer.printEnd = args.flag_end;
+    printer.printSection = args.flag_annotate;
+    printer.demangle = args.flag_demangle;
+    printer.printRaw = args.flag_raw;
+    let filter = if args.flag_all { SectionFilter::All } else { SectionFilter::fromName(&args.flag_sections) };
+    if args.flag_columns.len() > 0 {
+        printer.columns = Some(rstrings::parseColumns(&args.flag_columns).unwrap_or_else(|err| panic!("{}", err)));
+    }
 
     if args.flag_help {
         //do something
@@ -59,23 +156,56 @@ fn main(){
         std::process::exit(0);        
     }
 
-    if filename.len() == 0 { //if no filename specified, then we assume there should be something in stdin
-        let mut bytes: Vec<u8> = Vec::new(); //blank vector of u8s
-        let mut reader = io::stdin();
-        bytes = match reader.read_to_end(&mut bytes) { //read the whole file
-            Ok(x) => bytes, //standard ok() err()
-            Err(_) => panic!("Failed to read the file!"), //panic if we can't read from the file
+    if args.arg_file.len() == 0 && !args.flag_recursive { //if no filename specified, then we assume there should be something in stdin
+        printer.status("Successfully read input from stdin, starting to search. ");
+        if args.flag_threads == 1 { //stdin is streamed so pipes and block devices of any size can be searched
+            searchStream(io::stdin(), &options, &mut printer);
+        }
+        else {
+            let mut bytes: Vec<u8> = Vec::new(); //blank vector of u8s
+            let mut reader = io::stdin();
+            bytes = match reader.read_to_end(&mut bytes) { //the threaded search needs the whole file in memory
+                Ok(_) => bytes, //standard ok() err()
+                Err(_) => panic!("Failed to read the file!"), //panic if we can't read from the file
+            };
+            searchFile(&bytes, &options, &filter, &mut printer, args.flag_threads);
+        }
+        printer.finish();
+        std::process::exit(0);
+    }
+
+    let mut walker = FileWalker::new().recursive(args.flag_recursive).followSymlinks(args.flag_follow);
+    for pattern in args.flag_include.iter() {
+        walker = walker.include(pattern).unwrap_or_else(|err| panic!("{}", err));
+    }
+    for pattern in args.flag_exclude.iter() {
+        walker = walker.exclude(pattern).unwrap_or_else(|err| panic!("{}", err));
+    }
+    let paths = if args.arg_file.len() == 0 { vec![String::from(".")] } else { args.arg_file.clone() }; //like grep, a recursive search with no files searches the current directory
+    let mut files = walker.walk(&paths);
+
+    if paths.len() == 1 && files.len() == 1 && !args.flag_recursive { //searching a single file works like it always has
+        let filename = match files.remove(0) {
+            Ok(path) => path.display().to_string(),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            },
         };
-        println!("Successfully read input from stdin, starting to search. ");
-        searchFile(bytes, args.flag_bytes, args.flag_nullbytes, args.flag_filename, filename.clone(), args.flag_location, args.flag_removerepeats, args.flag_utf8, args.flag_threads, false);
+        printer.status(&format!("Opening {} to search it for strings...", filename));   
+        let file = openFile(filename.clone());
+        printer.status(&format!("Opened {}. ", filename));
+        printer.setFile(Some(filename));
+        searchInput(file, &options, &filter, &mut printer, args.flag_threads);
+        printer.finish();
         std::process::exit(0);
     }
-    
 
-    println!("Opening {} to search it for strings...", filename);   
-    let file = openFile(filename.clone());
-    println!("Opened {}. ", filename);
-    searchFile(file, args.flag_bytes, args.flag_nullbytes, args.flag_filename, filename.clone(), args.flag_location, args.flag_removerepeats, args.flag_utf8, args.flag_threads, false);
+    let haveFailed = searchFiles(files, &options, &filter, &mut printer, args.flag_threads);
+    printer.finish();
+    if haveFailed {
+        std::process::exit(1);
+    }
 }
 
 fn fastBadHash(str: String) -> u32 {  //bad hashing algorithm 32 bit version of: https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function
@@ -89,224 +219,319 @@ fn fastBadHash(str: String) -> u32 {  //bad hashing algorithm 32 bit version of:
     return hash as u32; //yes this is an unsafe conversion it is equivalent to % so it is fine
 }
 
-fn isPrintableASCII(char: u8) -> bool { //checks whether the character (as a u8) is printable
-    if char >= 32u8 && char <= 126u8 {  //printable is 32..126
-        return true;
-    }
-    return false;
+struct Printer { //prints the strings in the order they are given
+    format: Format,
+    printFile: bool,
+    filename: Option<String>, //None for stdin
+    printLocation: bool,
+    printEnd: bool,
+    printSection: bool,
+    demangle: bool, //print mangled symbol names demangled
+    printRaw: bool, //print the mangled name as well
+    offsets: OffsetFormat, //how locations are written
+    removeRepeats: bool,
+    tagEncoding: bool, //tag the string with its encoding so the output of several encodings can be told apart
+    columns: Option<Vec<Column>>, //the columns for csv and tsv, None to follow the flags
+    hashList: Vec<u32>, //serves as a cache of the last 10 hashes so we can avoid repeats
+    haveFoundAString: bool, //used so we can suggest the --nullbytes flag when it is needed 
 }
 
-fn isUTF8(file: Vec<u8>, index: usize) -> (bool, usize) {   //checks if the group of non-printable characters at index in file is a valid unicode character
-    if index >= file.len(){ //check to see if we are going to search out of bounds
-        return (false, 0);
-    }
-    let mut foundUTF8 = false;  //used to hold the current status of whether or not we found a utf-8 character
-    let mut len = 0;            //used to hold the length (in bytes) of the utf-8 character
-    let lenFile = file.len();   //used for out of range prevention
-    let buf = &[file[index], file[index+1], file[index+2], file[index+3]];
-    for i in 1..4 { //1,2,3
-        let s = match str::from_utf8(&buf[0..i]) { //convert it to a UTF-8
-            Ok(n) => {},
-            Err(err) => { //keep track of whether or not we found a UTF-8 char
-                if foundUTF8 == false {
-                    foundUTF8 = true;
-                    len = i+1;
-                }
-            },
+impl Printer {
+    fn new(options: &ScanOptions, format: Format, printFile: bool, printLocation: bool, removeRepeats: bool) -> Printer {
+        return Printer {
+            format: format,
+            printFile: printFile,
+            filename: None,
+            printLocation: printLocation,
+            printEnd: false,
+            printSection: false,
+            demangle: false,
+            printRaw: false,
+            offsets: OffsetFormat::default(),
+            removeRepeats: removeRepeats,
+            tagEncoding: options.getEncodings().len() > 1,
+            columns: None,
+            hashList: vec![0], //256 is an impossible? value from our hashing algorithm so we start it with that as a starting point
+            haveFoundAString: false,
         };
     }
-    return (foundUTF8, len);
-}
 
-fn checkForString(file: Vec<u8>, index: usize, numBytes: i32, nullBytes: bool, utf8: bool) -> (bool, u64) { //bool is whether or not we did, u64 is the length of it if we did
-    let mut isFound = false; //by default we never found it
-    let mut size = 0;   //size=0 is the default
-    let mut i = 0;  //used in the loop{} structure as a counter
-    if !utf8 {
-        loop {
-            if index+i >= file.len() {
-                break;
-            }
-            if isPrintableASCII(file[index+i]){  //if it is printable, then just loop to go to the next one
-                i += 1; //must increment it so we go to the next character in the file
-            }
-            else {
-                if !isPrintableASCII(file[index+i]){ //if it isn't printable then check if it is long enough yet
-                    size = i;
-                    if size > (numBytes - 1) as usize {   //if it is long enough, then check if we should check if it is null terminated; -1 is to fix OBO error so it will print things with 4 printable characters and 1 nullbyte
-                        if !nullBytes { //if nullBytes == true, then don't check for them
-                            if file[index+i] == 0 { //null terminated
-                                isFound = true; 
-                            }
-                        }
-                        else {
-                            isFound = true; 
-                        }
-                    } 
-                    break; //once we find a non-printable character we break
-                }
-            }
+    fn setFile(&mut self, filename: Option<String>) { //the strings printed after this come from filename
+        self.filename = filename;
+        self.hashList = vec![0]; //repeats don't carry over from one file to the next
+    }
+
+    fn status(&self, message: &str) { //messages for people go to stderr when the output is meant for a program
+        if self.format.isStructured() {
+            eprintln!("{}", message);
+        }
+        else {
+            println!("{}", message);
         }
     }
-    if utf8 {   //special string searching logic for utf-8 search since it is considerably slower than searching for ASCII strings
-        let mut numberOfCharacters = 0;     //must keep track of the numberOfCharacters alone since num of characters != num of bytes for UTF-8
-        loop {
-            if isPrintableASCII(file[index+i]){  //if it is printable, then just loop to go to the next one
-                i += 1; //must increment it so we go to the next character in the file
-                numberOfCharacters += 1;    //found 1 character
+
+    fn columns(&self) -> Vec<Column> { //the columns picked with --columns, or the same fields the text format would print
+        if let Some(ref columns) = self.columns {
+            return columns.clone();
+        }
+        let mut columns: Vec<Column> = Vec::new();
+        if self.printFile {
+            columns.push(Column::File);
+        }
+        if self.printLocation {
+            columns.push(Column::Offset);
+        }
+        if self.printEnd {
+            columns.push(Column::End);
+        }
+        if self.printSection {
+            columns.push(Column::Section);
+            columns.push(Column::Address);
+        }
+        if self.tagEncoding {
+            columns.push(Column::Encoding);
+        }
+        if !self.demangle || self.printRaw {
+            columns.push(Column::String);
+        }
+        if self.demangle {
+            columns.push(Column::Demangled);
+        }
+        return columns;
+    }
+
+    fn fileOf(&self, found: &FoundString) -> Option<String> { //the file a string is in, as archive!member for the files in archives
+        return match found.member {
+            Some(ref member) => Some(format!("{}!{}", self.filename.as_ref().map_or("", |filename| filename), member)),
+            None => self.filename.clone(),
+        };
+    }
+
+    fn print(&mut self, found: FoundString) {
+        let hash: u32 = fastBadHash(found.text.clone()); //get the hash of the string (via a *horrible* but fast hashing algorithm)
+        let mut allHashesEqual = true; 
+        for tempHash in self.hashList.iter() {
+            if *tempHash != hash {
+                allHashesEqual = false; //if any of the hashes don't match, then we don't skip
             }
-            else {
-                if !isPrintableASCII(file[index+i]){
-                    let mut utf8CheckerTuple = (false, 0);
-                    if index+i+1 < file.len() && file[index+i+1] > 127 {
-                        utf8CheckerTuple = isUTF8(file.clone(), index+i+1); //tuple of (whetherFoundUTF8, number of bytes of UTF8)
+        }
+        if ! (allHashesEqual && self.removeRepeats /*We found something that is being duplicated*/) { //if we don't need to skip it
+            match self.format {
+                Format::Text => {
+                    let mut line = String::new(); //each enabled field is followed by a colon
+                    if self.printFile || found.member.is_some() {
+                        line.push_str(&format!("{}:", self.fileOf(&found).unwrap_or_default()));
                     }
-                    else {
-                        utf8CheckerTuple = (false, 0); 
+                    if self.printLocation {
+                        line.push_str(&format!("{}:", self.offsets.formatLocation(found.stream, found.offset)));
                     }
-                    if utf8CheckerTuple.0 { //if we found a utf8 character
-                        numberOfCharacters += 1;      //we found one character but more than 1 byte
-                        i += utf8CheckerTuple.1 + 1; //add the number of bytes to our indexIterator
+                    if self.printEnd {
+                        line.push_str(&format!("{}:", self.offsets.formatLocation(found.stream, found.offset + found.length as u64)));
                     }
-                    else { //if not then check if we have a string
-                        size = i;
-                        if numberOfCharacters > (numBytes -1) { //prevents out of bounds read
-                            if !nullBytes { //if nullBytes == true, then don't check for them
-                                if file[index+i] == 0 { //null terminated
-                                    isFound = true; 
-                                }
-                            }
-                            else {
-                                isFound = true; 
-                            }
-                        }
-                        break;
+                    if self.printSection {
+                        line.push_str(&format!("{}:{}:", found.section.as_ref().map_or("", |section| section), found.address.map_or(String::new(), |address| self.offsets.format(address))));
                     }
-                }
-            }
-        }
-    }
-    return (isFound, size as u64)   //return it as a u64 so it is sufficiently large
-}
-
-fn searchFile(file: Vec<u8>, numBytes: i32, nullBytes: bool, printFile: bool, filename: String, printLocation: bool, removeRepeats: bool, utf8: bool, mut threads: i32, inThread: bool) { //given a vector of u8 will search the file
-    if threads == 1 {
-        let mut hashList: Vec<u32> = Vec::new(); //serves as a cache of the last 10 hashes so we can avoid repeats
-        hashList.push(0); //256 is an impossible? value from our hashing algorithm so we start it with that as a starting point
-        let mut haveFoundAString = false; //used so we can suggest the --nullbytes flag when it is needed 
-        let mut numToSkip = 0;  //the number to skip (used when we find a 5 character string so we don't then print a 4 character string followed by a 3 character and so on
-        for (index,char) in file.iter().enumerate() { //index,char b/c we need both
-            if numToSkip > 0 { //if we need to skip, do so
-                numToSkip -= 1; //decrement it so we don't skip forever 
-            }
-            else { //if not skipping: 
-                let temp = checkForString(file.clone(), index, numBytes, nullBytes, utf8); //temp is a tuple; temp.0 is whether or not we found one; temp.1 is the length of the string we found 
-                if temp.0 { //if temp.0 is true then we found a string
-                    haveFoundAString = true;
-                    let foundString: String = getString(file.clone(), index as u64, index as u64+temp.1);
-                    let hash: u32 = fastBadHash(foundString.clone()); //get the hash of the string (via a *horrible* but fast hashing algorithm)
-                    let mut allHashesEqual = true; 
-                    for tempHash in hashList.clone() {
-                        if tempHash != hash {
-                            allHashesEqual = false; //if any of the hashes don't match, then we don't skip
-                        }
+                    if self.tagEncoding {
+                        line.push_str(&format!("{}:", found.encoding.name()));
                     }
-                    if ! (allHashesEqual && removeRepeats /*We found something that is being duplicated*/) { //if we don't need to skip it
-                        if printFile && !printLocation {
-                            println!("{1}:{0}", foundString, filename);
-                        }
-                        else { //if else b/c of the borrower 
-                            if !printFile && printLocation {
-                                println!("{1}:{0}", foundString, index);
-                            }
-                            else {
-                                if printFile && printLocation {
-                                    println!("{1}:{2}:{0}", foundString, filename, index);
-                                }
-                                else {
-                                    if !printFile && !printLocation {
-                                        println!("{}", foundString);
-                                    }
-                                }
-                            }
+                    if !self.demangle {
+                        println!("{}{}", line, found.text);
+                    }
+                    else {
+                        let demangled = rstrings::demangle(&found.text);
+                        if self.printRaw {
+                            line.push_str(&format!("{}:", if demangled.is_some() { &found.text[..] } else { "" }));
                         }
+                        println!("{}{}", line, demangled.as_ref().unwrap_or(&found.text));
                     }
-                    if hashList.len() > 10 { //only if there are 10 cached hashes should we start removing them
-                        hashList.remove(0); //remnove the first (oldest) element in the cache
+                },
+                Format::Json => { //the array is left open so strings can be printed as they are found
+                    print!("{}\n{}", if self.haveFoundAString { "," } else { "[" }, rstrings::toJson(self.fileOf(&found).as_ref().map(|file| &file[..]), &found, &self.offsets, self.printEnd, self.printSection, self.demangle));
+                },
+                Format::Ndjson => println!("{}", rstrings::toJson(self.fileOf(&found).as_ref().map(|file| &file[..]), &found, &self.offsets, self.printEnd, self.printSection, self.demangle)),
+                Format::Csv | Format::Tsv => {
+                    let columns = self.columns();
+                    let newline = if self.format == Format::Csv { "\r\n" } else { "\n" }; //RFC 4180 ends CSV records with CRLF
+                    if !self.haveFoundAString {
+                        print!("{}{}", rstrings::toDelimitedHeader(&columns, self.format), newline);
                     }
-                    hashList.push(hash); //add the latest hash to the end of the cache
-                    numToSkip = temp.1; //now we need to skip the length of the string
-                }
+                    print!("{}{}", rstrings::toDelimited(&columns, self.format, &self.offsets, self.fileOf(&found).as_ref().map(|file| &file[..]), &found), newline);
+                },
             }
+            self.haveFoundAString = true;
         }
-        if !haveFoundAString && !inThread {
-            println!("Failed to find any strings. Are the strings null terminated? Try the --nullbytes flag to disable the null byte requirement. If you need UTF-8 support, use the --utf8 flag to enable utf8 support. ")
+        if self.hashList.len() > 10 { //only if there are 10 cached hashes should we start removing them
+            self.hashList.remove(0); //remnove the first (oldest) element in the cache
         }
+        self.hashList.push(hash); //add the latest hash to the end of the cache
     }
+
+    fn finish(&self) {
+        if self.format == Format::Json {
+            println!("{}", if self.haveFoundAString { "\n]" } else { "[]" });
+        }
+        if !self.haveFoundAString {
+            self.status("Failed to find any strings. Are the strings null terminated? Try the --nullbytes flag to disable the null byte requirement. If you need UTF-8 support, use the --utf8 flag to enable utf8 support. If the strings are wide (e.g. in a Windows binary), try --encoding=l. ");
+        }
+    }
+}
+
+fn streamStrings<R: Read, F: FnMut(FoundString)>(reader: R, options: &ScanOptions, callback: F) -> io::Result<()> { //scans a stream, decompressing it (up to the same limit as files) if it is compressed
+    let scanner = Scanner::new(options.clone());
+    let limit = if options.getDecompress() { options.getMaxExtracted() } else { 0 }; //nothing is decompressed with a limit of 0
+    return rstrings::scanDecompressed(&scanner, reader, limit, callback).map(|_| ());
+}
+
+fn searchStream<R: Read>(reader: R, options: &ScanOptions, printer: &mut Printer) { //searches the input a buffer at a time instead of reading it all into memory
+    if streamStrings(reader, options, |found| printer.print(found)).is_err() {
+        panic!("Failed to read the file!"); //panic if we can't read from the file
+    }
+}
+
+fn searchFile(file: &[u8], options: &ScanOptions, filter: &SectionFilter, printer: &mut Printer, mut threads: i32) { //given the bytes of a file will search the file (or the sections of it picked by filter)
     if threads == 0 {
         threads = num_cpus::get() as i32 * 16;
     }
-    if threads > 1 {
-        let mut files: Vec<Vec<u8>> = Vec::new(); //a vector of files (which can each be treated as a normal file)
-        let lenOriginaFile = file.len(); 
-        if lenOriginaFile < 1000 { //if it is less than 1KB
-            panic!("Cannot use multiple threads on files less than 1KB in size. ");
+    let scanner = Scanner::new(options.clone());
+    //with threads the threads each search a chunk of the file, the strings are printed in file order as soon as the chunks before them are done
+    rstrings::scanSections(&scanner, file, filter, threads as usize, |found| printer.print(found));
+}
+
+fn searchInput(input: Input, options: &ScanOptions, filter: &SectionFilter, printer: &mut Printer, threads: i32) {
+    match input {
+        Input::Mapped(map) => searchFile(&map, options, filter, printer, threads),
+        Input::Stream(file) => { //pipes, devices and the like can't be mapped so they are streamed instead (and so searched from end to end)
+            if threads == 1 {
+                searchStream(file, options, printer);
+                return;
+            }
+            let mut bytes: Vec<u8> = Vec::new();
+            let mut reader = file;
+            if reader.read_to_end(&mut bytes).is_err() {
+                panic!("Failed to read the file!");
+            }
+            searchFile(&bytes, options, filter, printer, threads);
+        },
+    }
+}
+
+fn collectStrings(path: &PathBuf, options: &ScanOptions, filter: &SectionFilter) -> io::Result<Vec<FoundString>> { //searches a whole file and returns what it found instead of printing it
+    let scanner = Scanner::new(options.clone());
+    return match tryOpenFile(path)? {
+        Input::Mapped(map) => {
+            let mut strings: Vec<FoundString> = Vec::new();
+            rstrings::scanSections(&scanner, &map, filter, 1, |found| strings.push(found));
+            Ok(strings)
+        },
+        Input::Stream(file) => {
+            let mut strings: Vec<FoundString> = Vec::new();
+            streamStrings(file, options, |found| strings.push(found))?;
+            Ok(strings)
+        },
+    };
+}
+
+fn searchFiles(files: Vec<io::Result<PathBuf>>, options: &ScanOptions, filter: &SectionFilter, printer: &mut Printer, mut threads: i32) -> bool { //searches several files, prefixing each string with its file; returns whether any file couldn't be searched
+    let mut haveFailed = false;
+    printer.printFile = true;
+    if threads == 1 { //one file after the other, printing as we go
+        for file in files {
+            let path = match file {
+                Ok(path) => path,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    haveFailed = true;
+                    continue;
+                },
+            };
+            let input = match tryOpenFile(&path) {
+                Ok(input) => input,
+                Err(err) => {
+                    eprintln!("{}: {}", path.display(), err);
+                    haveFailed = true;
+                    continue;
+                },
+            };
+            printer.setFile(Some(path.display().to_string()));
+            searchInput(input, options, filter, printer, 1);
         }
-        let lenChunkPerThread = lenOriginaFile / threads as usize; //the number of bytes given to each thread
-        for i in 0..threads { //initialize the vectors that will each hold the bytes in 1 file
-            files.push(Vec::new());
+        return haveFailed;
+    }
+    if threads == 0 {
+        threads = num_cpus::get() as i32;
+    }
+    let next = AtomicUsize::new(0); //the index of the next file a thread should pick up
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            let sender = sender.clone();
+            let next = &next;
+            let files = &files;
+            scope.spawn(move || {
+                loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    if index >= files.len() {
+                        break;
+                    }
+                    let result = match files[index] {
+                        Ok(ref path) => collectStrings(path, options, filter).map_err(|err| format!("{}: {}", path.display(), err)),
+                        Err(ref err) => Err(err.to_string()),
+                    };
+                    if sender.send((index, result)).is_err() {
+                        break;
+                    }
+                }
+            });
         }
-        let mut currentThread = 0;
-        for (index,byte) in file.iter().enumerate() { //splits the file into n files 
-            if index > 0 && index % lenChunkPerThread == 0 {
-                if !(currentThread == threads-1) {  //makes it so the extra n bytes at the end (from integer division) are just stuck on the last thread
-                    currentThread += 1
+        drop(sender); //so the receiver stops once every thread is done
+        let mut done: HashMap<usize, Result<Vec<FoundString>, String>> = HashMap::new(); //files that finished before the ones in front of them
+        let mut nextToPrint = 0;
+        for (index, result) in receiver.iter() {
+            done.insert(index, result);
+            while let Some(result) = done.remove(&nextToPrint) { //print the files in the order they were given
+                match result {
+                    Ok(strings) => {
+                        printer.setFile(files[nextToPrint].as_ref().map(|path| path.display().to_string()).ok());
+                        for found in strings {
+                            printer.print(found);
+                        }
+                    },
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        haveFailed = true;
+                    },
                 }
+                nextToPrint += 1;
             }
-            files[currentThread as usize].push(*byte);
-        }
-        for vec in files.iter() {
-            println!("Length: {:?}", vec.len());
-        }
-        let mut children = vec![];
-        let isInThread = true; 
-        for i in 0..threads {
-            let tempFile = files[i as usize].clone();
-            let tempFilename = filename.clone();
-            children.push(thread::spawn(move || {
-                searchFile(tempFile, numBytes, nullBytes, printFile, tempFilename, printLocation, removeRepeats, utf8, 1, isInThread);
-            }));
-        }
-        for child in children {
-            let _ = child.join();
         }
-    }
+    });
+    return haveFailed;
 }
 
-fn getString(file: Vec<u8>, startIndex: u64, endIndex: u64) -> String { //given the indexes in the file and the file, return the string
-    let mut vec: Vec<u8> = Vec::new();
-    for i in startIndex..endIndex { //go through each character that should be part of the string
-        vec.push(file[i as usize]);
-    }
-    let byteArr = &vec[..];
-    let str = match str::from_utf8(byteArr) {
-                    Ok(n) => n,
-                    Err(err) => "",
-                };
-    return String::from(str);
+enum Input {
+    Mapped(Mmap),   //regular files are mapped into memory
+    Stream(File),   //anything that can't be mapped (pipes, character devices, empty files) is read a buffer at a time
 }
 
-fn openFile(filename: String) -> Vec<u8> { //returns a vector of bytes (where byte == u8) in the file with the given filename
-    let file = match File::open(filename) { //this is creating the file variable
-        Ok(file) => file,                       //standard ok() and Err() syntax to check for errors
+fn openFile(filename: String) -> Input { //maps the file with the given filename into memory if it can
+    return match tryOpenFile(&PathBuf::from(filename)) {
+        Ok(input) => input,
         Err(_) => panic!("Failed to open the file!"), //if we can't open it, then panic
     };
+}
 
-    let mut bytes: Vec<u8> = Vec::new(); //blank vector of u8s
-    let mut reader = BufReader::new(file); //buffered reader so we can handle large files
-    return match reader.read_to_end(&mut bytes) { //read the whole file
-        Ok(x) => bytes, //standard ok() err()
-        Err(_) => panic!("Failed to read the file!"), //panic if we can't read from the file
+fn tryOpenFile(path: &PathBuf) -> io::Result<Input> {
+    let file = File::open(path)?;
+    let isMappable = match file.metadata() {
+        Ok(metadata) => metadata.is_file() && metadata.len() > 0, //zero length maps are an error
+        Err(_) => false,
+    };
+    if !isMappable {
+        return Ok(Input::Stream(file));
+    }
+    return match unsafe { Mmap::map(&file) } { //unsafe because the file could be changed underneath us, in which case we may print garbage but nothing worse
+        Ok(map) => Ok(Input::Mapped(map)),
+        Err(_) => Ok(Input::Stream(file)),
     };
 }
 
@@ -315,13 +540,16 @@ mod tests {
     use std::process::Command; //for executing external python test suite
 
     use std::str; //to read utf-8
+    use std::fs::File;
+    use std::io::Write;
+    use std::env;
+
+    extern crate flate2; //to make a compressed file too big to keep in the repo
+    use self::flate2::write::GzEncoder;
+    use self::flate2::Compression;
 
-    use super::getString;
-    use super::isUTF8;
-    use super::checkForString; 
     use super::fastBadHash;
     use super::openFile; 
-    use super::isPrintableASCII; 
 
     #[test]
     fn testHelloWorld() {
@@ -390,50 +618,311 @@ mod tests {
 
     #[test]
     fn testThreads() {
-        let status = Command::new("cargo").arg("run").arg("--").arg("--threads=4").arg("./testCases/a.out").output().unwrap_or_else(|e| {
+        let threaded = Command::new("cargo").arg("run").arg("--").arg("--threads=4").arg("--location").arg("./testCases/main").output().unwrap_or_else(|e| {
             panic!("failed to execute process: {}", e)
         });
-        let str = match str::from_utf8(&status.stdout) {
-                    Ok(n) => n,
-                    Err(err) => "",
-                };
-        let output = String::from(str);
-        assert_eq!(50093, fastBadHash(output)); //easier to embed a hash of the output than the output, the output is stored in the testcases directory. If this test fails check the cached output
+        let single = Command::new("cargo").arg("run").arg("--").arg("--location").arg("./testCases/main").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        assert_eq!(single.stdout, threaded.stdout); //the threads have to find exactly the same strings at the same locations in the same order
+    }
+
+    #[test]
+    fn testThreadsCachedOutput() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--threads=0").arg("--all").arg("./testCases/a.out").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let expected = openFile(String::from("./testCases/CachedOutput-a.outTHREADED"));
+        match expected {
+            super::Input::Mapped(map) => assert_eq!(&map[..], &status.stdout[..]),
+            super::Input::Stream(_) => panic!("the cached output should be a regular file"),
+        }
+    }
+
+    #[test]
+    fn testSeveralFiles() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--threads=3").arg("--nullbytes").arg("./testCases/a.out").arg("./testCases/repeated").arg("./testCases/main").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let mut expected: Vec<u8> = Vec::new(); //every file in order, each line starting with its name
+        for file in ["./testCases/a.out", "./testCases/repeated", "./testCases/main"].iter() {
+            let single = Command::new("cargo").arg("run").arg("--").arg("--nullbytes").arg("--filename").arg(file).output().unwrap_or_else(|e| {
+                panic!("failed to execute process: {}", e)
+            });
+            let single = String::from_utf8(single.stdout).unwrap();
+            for line in single.lines().skip(2) { //skip the "Opening"/"Opened" lines that are only printed for a single file
+                expected.extend_from_slice(line.as_bytes());
+                expected.push(b'\n');
+            }
+        }
+        assert_eq!(String::from_utf8(expected).unwrap(), String::from_utf8(status.stdout).unwrap());
+    }
+
+    #[test]
+    fn testNdjson() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--format=ndjson").arg("--nullbytes").arg("./testCases/repeated").arg("./testCases/short").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines.len() > 1);
+        for line in lines.iter() { //nothing but records on stdout
+            assert!(line.starts_with("{\"file\":\"./testCases/"));
+            assert!(line.ends_with("\"}"));
+        }
+    }
+
+    #[test]
+    fn testJson() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--format=json").arg("./testCases/a.out").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.starts_with("[\n{\"file\":\"./testCases/a.out\",\"offset\":568,\"length\":27,\"characters\":27,\"encoding\":\"ascii\",\"string\":\"/lib64/ld-linux-x86-64.so.2\"},\n"));
+        assert!(output.ends_with("}\n]\n"));
+    }
+
+    #[test]
+    fn testCsv() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--format=csv").arg("--columns=offset,end,string").arg("./testCases/a.out").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.starts_with("offset,end,string\r\n568,595,/lib64/ld-linux-x86-64.so.2\r\n"));
+        assert!(output.contains("\r\n2591,2596,\";*3$\"\"\"\r\n")); //quotes are doubled
+    }
+
+    #[test]
+    fn testRadix() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--location").arg("--end").arg("--radix=x8").arg("./testCases/a.out").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n00000238:00000253:/lib64/ld-linux-x86-64.so.2\n"));
+    }
+
+    #[test]
+    fn testSections() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--annotate").arg("--radix=x").arg("./testCases/a.out").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n.interp:400238:/lib64/ld-linux-x86-64.so.2\n"));
+        assert!(output.contains("\n.comment::GCC: (Ubuntu 4.8.4-2ubuntu1~14.04) 4.8.4\n"));
+        assert!(!output.contains("\n.strtab:")); //symbol names aren't data
+        let status = Command::new("cargo").arg("run").arg("--").arg("--annotate").arg("--all").arg("./testCases/a.out").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n.strtab::main\n"));
+    }
+
+    #[test]
+    fn testPe() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--annotate").arg("--radix=x").arg("--rva").arg("--resources").arg("./testCases/hello.exe").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n.rdata:2010:Hello from rdata\n.rsrc:string:1:30a4:Resource string one\n"));
+        assert!(output.ends_with("\n.rsrc:version:ProductVersion:3240:1.2.3\n"));
+    }
+
+    #[test]
+    fn testMachO() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--annotate").arg("--location").arg("./testCases/hello.fat").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n690:x86_64:__TEXT,__cstring:10:Hello from x86_64\n"));
+        assert!(output.ends_with("\n1329:arm64:__TEXT,__objc_methname:25:viewDidLoad\n"));
+    }
+
+    #[test]
+    fn testGo() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--go").arg("--annotate").arg("--radix=x").arg("./testCases/gohello").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n.rodata:402000:hello, world\n.rodata:40200c:Go is fun\n.rodata:402015:net/http\n.rodata:40201d:unexpected EOF\n"));
+        assert!(output.contains("\n.go.buildinfo:4030c1:go1.21.0\n"));
+        assert!(output.contains("\n.go.buildinfo:4030da:path\texample.com/hello\n"));
+    }
+
+    #[test]
+    fn testRust() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--rust").arg("--annotate").arg("--radix=x").arg("./testCases/rusthello").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n.rodata:402000:Hello, Rust!\n.rodata:40200c:src/main.rs\n"));
+        assert!(output.contains("\n.data.rel.ro:403fc0:src/main.rs:12:5\n.data.rel.ro:403fd8:src/main.rs:30:17\n"));
+    }
+
+    #[test]
+    fn testDotnet() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--dotnet").arg("--annotate").arg("--radix=x").arg("./testCases/hello.dll").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n#Strings:100020c8:Program\n"));
+        assert!(output.contains("\n#US:100020fa:Hello from .NET\n#US:1000211a:Wide literal\n"));
+        let status = Command::new("cargo").arg("run").arg("--").arg("--dotnet").arg("--sections=#US").arg("./testCases/hello.dll").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.ends_with("\nHello from .NET\nWide literal\n"));
     }
 
     #[test]
-    fn testGetString() {
-        let vec = vec![104u8, 105u8];
-        assert_eq!(String::from("hi"), getString(vec, 0, 2));
+    fn testJava() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--java").arg("--annotate").arg("./testCases/Hello.class").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\ncom/example/Hello:string::Hello from Java\n"));
+        assert!(output.contains("\ncom/example/Hello::java/io/PrintStream\n"));
+        let status = Command::new("cargo").arg("run").arg("--").arg("--java").arg("--filename").arg("--location").arg("./testCases/hello.jar").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n./testCases/hello.jar!com/example/Hello.class:"));
+        assert!(output.contains(":Hello from Java\n"));
     }
 
     #[test]
-    fn testIsUTF8() {
-        let vec = vec![62u8, 194u8, 162u8, 62u8];
-        assert_eq!((true, 3), isUTF8(vec.clone(), 0));
-        assert_eq!(String::from("¢"), getString(vec, 1, 3));
+    fn testDex() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--dex").arg("--dex-refs").arg("--annotate").arg("./testCases/hello.dex").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\nLHello;->main()V, LHello;->helper()V::Hello from DEX\n"));
+        assert!(output.contains("\n::Unused string\n"));
     }
 
     #[test]
-    fn testIsNotUTF8() {
-        let vec = vec![62u8, 62u8, 62u8, 62u8];
-        assert_eq!((false, 0), isUTF8(vec.clone(), 0));
+    fn testWasm() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--annotate").arg("--radix=x").arg("./testCases/hello.wasm").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\nimport::print_message\n"));
+        assert!(output.contains("\nexport::run_main\n"));
+        assert!(output.contains("\ndata[0]:400:Hello from wasm\ndata[0]:410:Second string\ndata[1]::passive segment text\ndata[2]:11170:far away data\n"));
+        assert!(output.contains("\nname:function::print_message\n"));
     }
 
-    //searchFile(file: Vec<u8>, numBytes: i32, nullBytes: bool, printFile: bool, filename: String, printLocation: bool, removeRepeats: bool, utf8: bool)
+    #[test]
+    fn testPdf() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--pdf").arg("--nullbytes").arg("--annotate").arg("--location").arg("./testCases/report.pdf").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n195+25:obj 4::Quarterly results\n"));
+        assert!(output.contains("\n588:obj 7::Quarterly (draft) report!\n"));
+        assert!(output.contains("\n784+39:obj 9::app.alert(\"Malicious script ran\");\n"));
+        let status = Command::new("cargo").arg("run").arg("--").arg("--nullbytes").arg("./testCases/report.pdf").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(!output.contains("Quarterly results"));
+    }
 
     #[test]
-    fn testCheckForString() {
-        let vec = vec![10u8, 62u8, 63u8, 64u8, 65u8, 66u8, 10u8, 63u8, 64u8, 65u8, 66u8, 67u8, 0u8, 12u8];
-        let numBytes = 4;
-        let mut nullBytes = false; 
-        let utf8 = false; 
-        assert_eq!((false, 0), checkForString(vec.clone(), 0, numBytes, nullBytes, utf8));
-        assert_eq!((false, 5), checkForString(vec.clone(), 1, numBytes, nullBytes, utf8));
-        assert_eq!((false, 4), checkForString(vec.clone(), 2, numBytes, nullBytes, utf8));
-        assert_eq!((true, 5), checkForString(vec.clone(), 7, numBytes, nullBytes, utf8));
-        nullBytes = true;
-        assert_eq!((true, 5), checkForString(vec.clone(), 1, numBytes, nullBytes, utf8));
+    fn testDecompress() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--nullbytes").arg("--location").arg("./testCases/log.txt.gz").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n0+7099:2024-03-09 13:37:00 ERROR secret token leaked: hunter2\n"));
+        let status = Command::new("cargo").arg("run").arg("--").arg("--nullbytes").arg("--location").stdin(File::open("./testCases/log.txt.zst").unwrap()).output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n0+7099:2024-03-09 13:37:00 ERROR secret token leaked: hunter2\n"));
+        let status = Command::new("cargo").arg("run").arg("--").arg("--nullbytes").arg("--no-decompress").arg("./testCases/log.txt.xz").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(!output.contains("hunter2"));
+        let path = env::temp_dir().join("rstrings-not-zlib.txt"); //text that starts like a zlib header
+        File::create(&path).unwrap().write_all(b"HKeep this line: a plain text note\n(Szall systems nominal, report follows here\n").unwrap();
+        let status = Command::new("cargo").arg("run").arg("--").arg("--nullbytes").arg("--location").stdin(File::open(&path).unwrap()).output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n0:HKeep this line: a plain text note\n"));
+    }
+
+    #[test]
+    fn testMaxExtract() {
+        let mut log = String::new();
+        for line in 0..60000 { //1.3MB
+            log.push_str(&format!("line {:06} of the log\n", line));
+        }
+        let mut stored = GzEncoder::new(Vec::new(), Compression::none()); //stored, so the lines past the limit can still be found as they are
+        stored.write_all(log.as_bytes()).unwrap();
+        let path = env::temp_dir().join("rstrings-max-extract.log.gz");
+        File::create(&path).unwrap().write_all(&stored.finish().unwrap()).unwrap();
+        let status = Command::new("cargo").arg("run").arg("--").arg("--nullbytes").arg("--location").arg("--max-extract=1").arg(&path).output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let lines = |output: Vec<u8>| -> Vec<String> { String::from_utf8(output).unwrap().lines().filter(|line| line.contains(" of the log")).map(String::from).collect() }; //without the status messages
+        let fromFile = lines(status.stdout);
+        assert_eq!("0+0:line 000000 of the log", fromFile[0]);
+        let last = fromFile.last().unwrap();
+        assert!(last.ends_with(":line 059999 of the log") && !last.contains('+'), "{}", last); //searched as it is, at its offset in the file
+        let status = Command::new("cargo").arg("run").arg("--").arg("--nullbytes").arg("--location").arg("--max-extract=1").stdin(File::open(&path).unwrap()).output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        assert_eq!(fromFile, lines(status.stdout));
+    }
+
+    #[test]
+    fn testCarve() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--location").arg("--carve").arg("./testCases/firmware.bin").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n199+30:The root password is changeme\n"));
+        assert!(output.contains("\n289+0:lzma squashed config: telnetd enabled\n"));
+        assert!(output.contains("\n389:plain firmware string\n"));
+        let status = Command::new("cargo").arg("run").arg("--").arg("./testCases/firmware.bin").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(!output.contains("changeme"));
+    }
+
+    #[test]
+    fn testArchives() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--location").arg("./testCases/bundle.tar").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n./testCases/bundle.tar!bundle/notes.txt:0:Remember the milk\n"));
+        assert!(output.contains("\n./testCases/bundle.tar!bundle/inner.zip!deep/secret.txt:0:The inner secret is swordfish\n"));
+        let status = Command::new("cargo").arg("run").arg("--").arg("--location").arg("./testCases/hello.deb").arg("./testCases/hello.rpm").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.lines().any(|line| line == "./testCases/hello.deb!data.tar.xz!./usr/share/doc/hello/message.txt:0:Hello from the deb package"));
+        assert!(output.lines().any(|line| line == "./testCases/hello.rpm!payload!./usr/bin/hello-script:10:echo Hello from the rpm payload"));
+        let status = Command::new("cargo").arg("run").arg("--").arg("--archive-depth=1").arg("./testCases/bundle.tar").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n./testCases/bundle.tar!bundle/notes.txt:Remember the milk\n"));
+        assert!(!output.contains("swordfish")); //the zip archive in it is searched as it is
+    }
+
+    #[test]
+    fn testDemangle() {
+        let status = Command::new("cargo").arg("run").arg("--").arg("--all").arg("--demangle").arg("--raw").arg("./testCases/main").output().unwrap_or_else(|e| {
+            panic!("failed to execute process: {}", e)
+        });
+        let output = String::from_utf8(status.stdout).unwrap();
+        assert!(output.contains("\n_ZN13std..fs..File9drop.255017hb0fe64ef671ab904E:std::fs::File::drop.2550\n"));
+        assert!(output.contains("\n:GLIBC_2.2.5\n")); //not a mangled name
     }
 
     #[test]
@@ -445,10 +934,4 @@ mod tests {
     fn testOpenFile() {
         openFile(String::from("/home/david/code/strings/RStrings/src/main.rs"));
     }
-
-    #[test]
-    fn testIsASCII() {
-        assert_eq!(true, isPrintableASCII(97u8));
-        assert_eq!(false, isPrintableASCII(10u8));
-    }
 }
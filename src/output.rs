@@ -0,0 +1,297 @@
+use rustc_serialize::json;
+
+use scanner::FoundString;
+use demangle::demangle;
+
+/// The ways found strings can be written out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,       //one string per line, optionally prefixed by its file, location and encoding
+    Json,       //a single JSON array of records
+    Ndjson,     //one JSON record per line
+    Csv,        //comma separated columns, quoted as in RFC 4180
+    Tsv,        //tab separated columns, with tabs and newlines in the string escaped
+}
+
+impl Format {
+    pub fn fromName(name: &str) -> Option<Format> {
+        return match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "ndjson" => Some(Format::Ndjson),
+            "csv" => Some(Format::Csv),
+            "tsv" => Some(Format::Tsv),
+            _ => None,
+        };
+    }
+
+    /// Whether the output is meant to be read by a program, in which case nothing but the records should go to stdout.
+    pub fn isStructured(&self) -> bool {
+        return *self != Format::Text;
+    }
+}
+
+/// How offsets are written: in decimal, octal or hex (like `-t d/o/x` in GNU strings), optionally zero padded.
+///
+/// ```
+/// use rstrings::OffsetFormat;
+/// assert_eq!("00000238", OffsetFormat::fromName("x8").unwrap().format(568));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OffsetFormat {
+    radix: u32,     //8, 10 or 16
+    width: usize,   //the number of digits to zero pad to, 0 for no padding
+}
+
+impl OffsetFormat {
+    /// Parses d, o or x, optionally followed by the number of digits to pad to (e.g. "x8").
+    pub fn fromName(name: &str) -> Result<OffsetFormat, String> {
+        let (radix, maxWidth) = match name.chars().next() { //at most the number of digits of the largest offset
+            Some('d') => (10, 20),
+            Some('o') => (8, 22),
+            Some('x') => (16, 16),
+            _ => return Err(format!("Unknown radix {}, valid radixes are d, o and x, optionally followed by a width (e.g. x8). ", name)),
+        };
+        let width = match &name[1..] {
+            "" => 0,
+            digits => match digits.parse::<usize>() {
+                Ok(width) if width <= maxWidth => width,
+                _ => return Err(format!("Invalid width {} in radix {}, it can be at most {}. ", digits, name, maxWidth)),
+            },
+        };
+        return Ok(OffsetFormat { radix: radix, width: width });
+    }
+
+    /// Whether offsets are plain decimal numbers, in which case JSON can hold them as numbers rather than strings.
+    pub fn isDecimal(&self) -> bool {
+        return self.radix == 10 && self.width == 0;
+    }
+
+    pub fn format(&self, offset: u64) -> String {
+        return match self.radix {
+            8 => format!("{:01$o}", offset, self.width),
+            16 => format!("{:01$x}", offset, self.width),
+            _ => format!("{:01$}", offset, self.width),
+        };
+    }
+
+    /// Formats a location in the input: offset, or for a string decompressed from a stream the offset of the stream and
+    /// the offset in what it decompresses to joined by a `+` (e.g. `0+1234`).
+    pub fn formatLocation(&self, stream: Option<u64>, offset: u64) -> String {
+        return match stream {
+            Some(stream) => format!("{}+{}", self.format(stream), self.format(offset)),
+            None => self.format(offset),
+        };
+    }
+}
+
+impl Default for OffsetFormat {
+    fn default() -> OffsetFormat {
+        return OffsetFormat { radix: 10, width: 0 };
+    }
+}
+
+/// The columns that can be picked for the CSV and TSV formats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Column {
+    File,       //empty for stdin
+    Offset,     //of the first byte of the string
+    End,        //the offset just past the last byte of the string
+    Length,     //in bytes
+    Encoding,
+    Section,    //the section of the file the string is in, empty if it isn't known
+    Address,    //the virtual address the string is loaded at, empty if it isn't known
+    String,
+    Demangled,  //the string demangled if it is a mangled symbol name, otherwise the string as it is
+}
+
+impl Column {
+    pub fn fromName(name: &str) -> Option<Column> {
+        return match name {
+            "file" => Some(Column::File),
+            "offset" => Some(Column::Offset),
+            "end" => Some(Column::End),
+            "length" => Some(Column::Length),
+            "encoding" => Some(Column::Encoding),
+            "section" => Some(Column::Section),
+            "address" => Some(Column::Address),
+            "string" => Some(Column::String),
+            "demangled" => Some(Column::Demangled),
+            _ => None,
+        };
+    }
+
+    pub fn name(&self) -> &'static str {
+        return match *self {
+            Column::File => "file",
+            Column::Offset => "offset",
+            Column::End => "end",
+            Column::Length => "length",
+            Column::Encoding => "encoding",
+            Column::Section => "section",
+            Column::Address => "address",
+            Column::String => "string",
+            Column::Demangled => "demangled",
+        };
+    }
+}
+
+/// Parses a comma separated list of columns such as "file,offset,string".
+pub fn parseColumns(names: &str) -> Result<Vec<Column>, String> {
+    let mut columns: Vec<Column> = Vec::new();
+    for name in names.split(',') {
+        match Column::fromName(name.trim()) {
+            Some(column) => columns.push(column),
+            None => return Err(format!("Unknown column {}, valid columns are file, offset, end, length, encoding, section, address, string and demangled. ", name)),
+        }
+    }
+    return Ok(columns);
+}
+
+fn csvField(field: &str) -> String { //fields with a comma, quote or line break in them are quoted, with quotes doubled
+    if !field.contains(|c| c == ',' || c == '"' || c == '\r' || c == '\n') {
+        return String::from(field);
+    }
+    return format!("\"{}\"", field.replace("\"", "\"\""));
+}
+
+fn tsvField(field: &str) -> String { //TSV can't quote so tabs and line breaks are escaped
+    return field.replace("\\", "\\\\").replace("\t", "\\t").replace("\n", "\\n").replace("\r", "\\r");
+}
+
+fn joinFields(fields: Vec<String>, format: Format) -> String {
+    if format == Format::Tsv {
+        return fields.iter().map(|field| tsvField(field)).collect::<Vec<String>>().join("\t");
+    }
+    return fields.iter().map(|field| csvField(field)).collect::<Vec<String>>().join(",");
+}
+
+/// The header line of a CSV or TSV file with the given columns.
+pub fn toDelimitedHeader(columns: &[Column], format: Format) -> String {
+    return joinFields(columns.iter().map(|column| String::from(column.name())).collect(), format);
+}
+
+/// Formats a found string as a line of a CSV (or TSV if `format` is `Format::Tsv`) file. `file` is `None` for stdin.
+pub fn toDelimited(columns: &[Column], format: Format, offsets: &OffsetFormat, file: Option<&str>, found: &FoundString) -> String {
+    let mut fields: Vec<String> = Vec::new();
+    for column in columns.iter() {
+        fields.push(match *column {
+            Column::File => String::from(file.unwrap_or("")),
+            Column::Offset => offsets.formatLocation(found.stream, found.offset),
+            Column::End => offsets.formatLocation(found.stream, found.offset + found.length as u64),
+            Column::Length => found.length.to_string(),
+            Column::Encoding => String::from(found.encoding.name()),
+            Column::Section => found.section.clone().unwrap_or_default(),
+            Column::Address => found.address.map_or(String::new(), |address| offsets.format(address)),
+            Column::String => found.text.clone(),
+            Column::Demangled => demangle(&found.text).unwrap_or_else(|| found.text.clone()),
+        });
+    }
+    return joinFields(fields, format);
+}
+
+fn jsonString(str: &str) -> String { //quotes and escapes str
+    return json::encode(&str).unwrap();
+}
+
+fn jsonOffset(offsets: &OffsetFormat, offset: u64) -> String { //a number, or a string when it isn't in plain decimal
+    if offsets.isDecimal() {
+        return offset.to_string();
+    }
+    return jsonString(&offsets.format(offset));
+}
+
+/// Formats a found string as a JSON object. `file` is `None` for stdin. For a string decompressed from a stream the
+/// offset of the stream is included (as `stream`), with the offsets in what it decompresses to. The end offset is only
+/// included when `printEnd` is set, the section and address only when `printSection` is (as null when they aren't known)
+/// and the demangled name only when `printDemangled` is (as null when the string isn't a mangled name).
+pub fn toJson(file: Option<&str>, found: &FoundString, offsets: &OffsetFormat, printEnd: bool, printSection: bool, printDemangled: bool) -> String {
+    let file = match file {
+        Some(file) => jsonString(file),
+        None => String::from("null"),
+    };
+    let mut extra = String::new(); //the optional fields
+    if let Some(stream) = found.stream {
+        extra.push_str(&format!(",\"stream\":{}", jsonOffset(offsets, stream)));
+    }
+    if printEnd {
+        extra.push_str(&format!(",\"end\":{}", jsonOffset(offsets, found.offset + found.length as u64)));
+    }
+    if printSection {
+        extra.push_str(&format!(",\"section\":{},\"address\":{}", found.section.as_ref().map_or(String::from("null"), |section| jsonString(section)),
+            found.address.map_or(String::from("null"), |address| jsonOffset(offsets, address))));
+    }
+    let demangled = if printDemangled { format!(",\"demangled\":{}", demangle(&found.text).map_or(String::from("null"), |demangled| jsonString(&demangled))) } else { String::new() };
+    return format!("{{\"file\":{},\"offset\":{}{},\"length\":{},\"characters\":{},\"encoding\":{},\"string\":{}{}}}",
+        file, jsonOffset(offsets, found.offset), extra, found.length, found.text.chars().count(), jsonString(found.encoding.name()), jsonString(&found.text), demangled);
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding::Encoding;
+    use scanner::FoundString;
+    use super::{Format, Column, OffsetFormat, toJson, parseColumns, toDelimited, toDelimitedHeader};
+
+    #[test]
+    fn testFormatFromName() {
+        assert_eq!(Some(Format::Ndjson), Format::fromName("ndjson"));
+        assert_eq!(None, Format::fromName("xml"));
+        assert!(!Format::Text.isStructured());
+    }
+
+    #[test]
+    fn testToJson() {
+        let found = FoundString { offset: 16, length: 10, encoding: Encoding::LittleEndian16, text: String::from("a:b\n\""), section: None, address: None, member: None, stream: None };
+        assert_eq!("{\"file\":\"dir/x.exe\",\"offset\":16,\"length\":10,\"characters\":5,\"encoding\":\"utf16le\",\"string\":\"a:b\\n\\\"\"}", toJson(Some("dir/x.exe"), &found, &OffsetFormat::default(), false, false, false));
+        let found = FoundString { offset: 0, length: 3, encoding: Encoding::SevenBit, text: String::from("¢h"), section: None, address: None, member: None, stream: None };
+        assert_eq!("{\"file\":null,\"offset\":0,\"length\":3,\"characters\":2,\"encoding\":\"ascii\",\"string\":\"¢h\"}", toJson(None, &found, &OffsetFormat::default(), false, false, false));
+        let hex = OffsetFormat::fromName("x4").unwrap();
+        assert_eq!("{\"file\":null,\"offset\":\"0010\",\"end\":\"0013\",\"length\":3,\"characters\":2,\"encoding\":\"ascii\",\"string\":\"¢h\"}", toJson(None, &FoundString { offset: 16, ..found.clone() }, &hex, true, false, false));
+        let found = FoundString { section: Some(String::from(".rodata")), address: Some(0x400960), ..found };
+        assert_eq!("{\"file\":null,\"offset\":0,\"section\":\".rodata\",\"address\":4196704,\"length\":3,\"characters\":2,\"encoding\":\"ascii\",\"string\":\"¢h\"}", toJson(None, &found, &OffsetFormat::default(), false, true, false));
+        let found = FoundString { text: String::from("_ZN3foo3barEv"), length: 13, ..found };
+        assert!(toJson(None, &found, &OffsetFormat::default(), false, false, true).ends_with(",\"string\":\"_ZN3foo3barEv\",\"demangled\":\"foo::bar()\"}"));
+        let found = FoundString { stream: Some(0), section: None, address: None, ..found };
+        assert!(toJson(None, &found, &OffsetFormat::default(), true, false, false).starts_with("{\"file\":null,\"offset\":0,\"stream\":0,\"end\":13,"));
+    }
+
+    #[test]
+    fn testOffsetFormat() {
+        assert_eq!("568", OffsetFormat::default().format(568));
+        assert_eq!("1070", OffsetFormat::fromName("o").unwrap().format(568));
+        assert_eq!("238", OffsetFormat::fromName("x").unwrap().format(568));
+        assert_eq!("00000568", OffsetFormat::fromName("d8").unwrap().format(568));
+        assert_eq!("123456", OffsetFormat::fromName("x2").unwrap().format(0x123456)); //padding never truncates
+        assert!(OffsetFormat::fromName("h").is_err());
+        assert!(OffsetFormat::fromName("x-1").is_err());
+        assert!(OffsetFormat::fromName("x999999999").is_err());
+        assert!(OffsetFormat::fromName("x17").is_err());
+        assert!(OffsetFormat::fromName("d20").is_ok());
+        assert!(OffsetFormat::fromName("").is_err());
+        assert_eq!("238", OffsetFormat::fromName("x").unwrap().formatLocation(None, 568));
+        assert_eq!("10+238", OffsetFormat::fromName("x").unwrap().formatLocation(Some(16), 568));
+    }
+
+    #[test]
+    fn testParseColumns() {
+        assert_eq!(Ok(vec![Column::File, Column::End, Column::String]), parseColumns("file, end,string"));
+        assert!(parseColumns("file,size").is_err());
+    }
+
+    #[test]
+    fn testToDelimited() {
+        let columns = vec![Column::File, Column::Offset, Column::End, Column::Length, Column::Encoding, Column::Section, Column::String];
+        assert_eq!("file,offset,end,length,encoding,section,string", toDelimitedHeader(&columns, Format::Csv));
+        let found = FoundString { offset: 16, length: 10, encoding: Encoding::LittleEndian16, text: String::from("say \"hi\", then\tgo"), section: None, address: None, member: None, stream: None };
+        assert_eq!("\"a,b.exe\",16,26,10,utf16le,,\"say \"\"hi\"\", then\tgo\"", toDelimited(&columns, Format::Csv, &OffsetFormat::default(), Some("a,b.exe"), &found));
+        assert_eq!("a,b.exe\t16\t26\t10\tutf16le\t\tsay \"hi\", then\\tgo", toDelimited(&columns, Format::Tsv, &OffsetFormat::default(), Some("a,b.exe"), &found));
+        assert_eq!("10,1a", toDelimited(&[Column::Offset, Column::End], Format::Csv, &OffsetFormat::fromName("x").unwrap(), None, &found));
+        let found = FoundString { offset: 0, length: 5, encoding: Encoding::SevenBit, text: String::from("plain"), section: None, address: None, member: None, stream: None };
+        assert_eq!(",plain", toDelimited(&[Column::File, Column::String], Format::Csv, &OffsetFormat::default(), None, &found));
+        let found = FoundString { section: Some(String::from(".rodata")), address: Some(0x400960), ..found };
+        assert_eq!(".rodata,400960,plain", toDelimited(&[Column::Section, Column::Address, Column::String], Format::Csv, &OffsetFormat::fromName("x").unwrap(), None, &found));
+        assert_eq!("plain,plain", toDelimited(&[Column::String, Column::Demangled], Format::Csv, &OffsetFormat::default(), None, &found));
+        let found = FoundString { text: String::from("_ZN3foo3barEii"), ..found };
+        assert_eq!("_ZN3foo3barEii,\"foo::bar(int, int)\"", toDelimited(&[Column::String, Column::Demangled], Format::Csv, &OffsetFormat::default(), None, &found));
+    }
+}
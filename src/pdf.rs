@@ -0,0 +1,549 @@
+use std::cmp;
+use std::cell::Cell;
+use std::str;
+use std::collections::HashSet;
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+
+use encoding::Encoding;
+use scanner::{Scanner, FoundString};
+
+const PDF_MAGIC: &'static [u8] = b"%PDF-";
+const HEADER_WINDOW: usize = 1024; //readers accept the header anywhere in the first 1KB, and malicious files make use of it
+const SPACE_KERNING: f64 = -200.0; //a gap in a TJ array at least this wide (in thousandths of a text unit) is taken for a space
+
+pub fn isPdf(data: &[u8]) -> bool {
+    return data[..cmp::min(data.len(), HEADER_WINDOW)].windows(PDF_MAGIC.len()).any(|window| window == PDF_MAGIC);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(usize, usize),     //a number or a keyword (like obj, stream or R), by where it is
+    Name(Vec<u8>),          //without the slash, with #xx escapes decoded (as /Fl#61teDecode hides /FlateDecode)
+    Literal(usize, usize),  //the bytes between the parentheses of a (string)
+    Hex(usize, usize),      //the bytes between the angle brackets of a <hex string>
+    Dictionary(bool),       //<< or >>
+    Array(bool),            //[ or ]
+}
+
+fn isWhitespace(byte: u8) -> bool {
+    return byte == 0 || byte == b'\t' || byte == b'\n' || byte == 0x0c || byte == b'\r' || byte == b' ';
+}
+
+fn isDelimiter(byte: u8) -> bool {
+    return b"()<>[]{}/%".contains(&byte);
+}
+
+fn hexValue(byte: u8) -> Option<u8> {
+    return (byte as char).to_digit(16).map(|value| value as u8);
+}
+
+struct Lexer<'a> { //the tokens of PDF syntax, with comments and stray delimiters skipped
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = (usize, Token); //where the token starts, and the token
+
+    fn next(&mut self) -> Option<(usize, Token)> {
+        let data = self.data;
+        loop {
+            while self.position < data.len() && isWhitespace(data[self.position]) {
+                self.position += 1;
+            }
+            let start = self.position;
+            let byte = *data.get(start)?;
+            let next = data.get(start + 1).cloned();
+            match byte {
+                b'%' => { //a comment, to the end of the line
+                    while self.position < data.len() && data[self.position] != b'\n' && data[self.position] != b'\r' {
+                        self.position += 1;
+                    }
+                },
+                b'(' => {
+                    let mut depth = 1; //parentheses in a string only need escaping when unbalanced
+                    let mut index = start + 1;
+                    while index < data.len() {
+                        match data[index] {
+                            b'\\' => index += 1,
+                            b'(' => depth += 1,
+                            b')' => { depth -= 1; if depth == 0 { break; } },
+                            _ => {},
+                        }
+                        index += 1;
+                    }
+                    let end = cmp::min(index, data.len());
+                    self.position = cmp::min(end + 1, data.len());
+                    return Some((start, Token::Literal(start + 1, end)));
+                },
+                b'<' if next == Some(b'<') => {
+                    self.position += 2;
+                    return Some((start, Token::Dictionary(true)));
+                },
+                b'>' if next == Some(b'>') => {
+                    self.position += 2;
+                    return Some((start, Token::Dictionary(false)));
+                },
+                b'<' => {
+                    let end = data[start..].iter().position(|&byte| byte == b'>').map_or(data.len(), |end| start + end);
+                    self.position = cmp::min(end + 1, data.len());
+                    return Some((start, Token::Hex(start + 1, end)));
+                },
+                b'[' | b']' => {
+                    self.position += 1;
+                    return Some((start, Token::Array(byte == b'[')));
+                },
+                b'/' => {
+                    self.position += 1;
+                    let mut name: Vec<u8> = Vec::new();
+                    while self.position < data.len() && !isWhitespace(data[self.position]) && !isDelimiter(data[self.position]) {
+                        let escaped = if data[self.position] == b'#' && self.position + 2 < data.len() {
+                            hexValue(data[self.position + 1]).and_then(|high| hexValue(data[self.position + 2]).map(|low| high << 4 | low))
+                        } else { None };
+                        match escaped {
+                            Some(byte) => { name.push(byte); self.position += 3; },
+                            None => { name.push(data[self.position]); self.position += 1; },
+                        }
+                    }
+                    return Some((start, Token::Name(name)));
+                },
+                _ if isDelimiter(byte) => self.position += 1, //a stray ) or >, or the braces of PostScript functions
+                _ => {
+                    while self.position < data.len() && !isWhitespace(data[self.position]) && !isDelimiter(data[self.position]) {
+                        self.position += 1;
+                    }
+                    return Some((start, Token::Word(start, self.position)));
+                },
+            }
+        }
+    }
+}
+
+fn lex<'a>(data: &'a [u8], start: usize) -> Lexer<'a> {
+    return Lexer { data: data, position: start };
+}
+
+fn integer(data: &[u8], token: &Token) -> Option<u64> {
+    return match *token {
+        Token::Word(start, end) => str::from_utf8(&data[start..end]).ok()?.parse().ok(),
+        _ => None,
+    };
+}
+
+fn isWord(data: &[u8], token: &Token, word: &[u8]) -> bool {
+    return match *token {
+        Token::Word(start, end) => &data[start..end] == word,
+        _ => false,
+    };
+}
+
+/// Decodes the bytes of a (literal string): its escapes, and its line ends (which all stand for \n).
+fn decodeLiteral(bytes: &[u8]) -> Vec<u8> {
+    let mut decoded: Vec<u8> = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        index += 1;
+        if byte == b'\r' {
+            index += if bytes.get(index) == Some(&b'\n') { 1 } else { 0 };
+            decoded.push(b'\n');
+            continue;
+        }
+        if byte != b'\\' || index == bytes.len() {
+            decoded.push(byte);
+            continue;
+        }
+        let escaped = bytes[index];
+        index += 1;
+        match escaped {
+            b'n' => decoded.push(b'\n'),
+            b'r' => decoded.push(b'\r'),
+            b't' => decoded.push(b'\t'),
+            b'b' => decoded.push(8),
+            b'f' => decoded.push(0x0c),
+            b'\r' => index += if bytes.get(index) == Some(&b'\n') { 1 } else { 0 }, //a line continued on the next
+            b'\n' => {},
+            b'0'..=b'7' => { //up to 3 octal digits
+                let mut value = (escaped - b'0') as u32;
+                let mut digits = 1;
+                while digits < 3 && index < bytes.len() && bytes[index] >= b'0' && bytes[index] <= b'7' {
+                    value = value * 8 + (bytes[index] - b'0') as u32;
+                    index += 1;
+                    digits += 1;
+                }
+                decoded.push(value as u8); //overflow is ignored, as the spec says
+            },
+            _ => decoded.push(escaped), //\( \) \\ and unknown escapes, whose backslash is dropped
+        }
+    }
+    return decoded;
+}
+
+/// Decodes the bytes of a <hex string>, whose whitespace is ignored and whose last digit is 0 if it is missing.
+fn decodeHex(bytes: &[u8]) -> Vec<u8> {
+    let digits: Vec<u8> = bytes.iter().filter_map(|&byte| hexValue(byte)).collect();
+    return digits.chunks(2).map(|pair| pair[0] << 4 | pair.get(1).cloned().unwrap_or(0)).collect();
+}
+
+/// The text of a string's bytes: UTF-16BE after a byte order mark, UTF-8 after one (as PDF 2.0 allows), and otherwise
+/// PDFDocEncoding, taken to be Latin-1 (they only differ in a few punctuation characters).
+fn decodeText(bytes: &[u8]) -> (String, Encoding) {
+    if bytes.starts_with(b"\xfe\xff") {
+        let units: Vec<u16> = bytes[2..].chunks(2).filter(|pair| pair.len() == 2).map(|pair| (pair[0] as u16) << 8 | pair[1] as u16).collect();
+        return (String::from_utf16_lossy(&units), Encoding::BigEndian16);
+    }
+    if bytes.starts_with(b"\xef\xbb\xbf") {
+        return (String::from_utf8_lossy(&bytes[3..]).into_owned(), Encoding::SevenBit);
+    }
+    let encoding = if bytes.iter().all(|&byte| byte < 0x80) { Encoding::SevenBit } else { Encoding::EightBit };
+    return (bytes.iter().map(|&byte| byte as char).collect(), encoding);
+}
+
+struct PdfString { //a string found in PDF syntax, before it is turned into a FoundString
+    start: usize,       //where its bytes start and end, between the delimiters
+    end: usize,
+    bytes: Vec<u8>,     //decoded
+}
+
+fn stringBytes(data: &[u8], token: &Token) -> Option<PdfString> {
+    return match *token {
+        Token::Literal(start, end) => Some(PdfString { start: start, end: end, bytes: decodeLiteral(&data[start..end]) }),
+        Token::Hex(start, end) => Some(PdfString { start: start, end: end, bytes: decodeHex(&data[start..end]) }),
+        _ => None,
+    };
+}
+
+#[derive(Default)]
+struct Contents { //picks up the objects named by /Contents entries (the content streams of pages)
+    objects: HashSet<u64>,
+    inEntry: bool,
+    inArray: bool,
+    numbers: Vec<u64>,
+}
+
+impl Contents {
+    fn see(&mut self, data: &[u8], token: &Token) {
+        if let Token::Name(ref name) = *token {
+            self.inEntry = &name[..] == b"Contents";
+            self.inArray = false;
+            self.numbers.clear();
+            return;
+        }
+        if !self.inEntry {
+            return;
+        }
+        match (integer(data, token), token) {
+            (Some(number), _) => self.numbers.push(number),
+            (None, &Token::Array(true)) if !self.inArray => self.inArray = true,
+            (None, _) if isWord(data, token, b"R") && self.numbers.len() >= 2 => {
+                self.objects.insert(self.numbers[self.numbers.len() - 2]);
+                self.numbers.clear();
+                self.inEntry = self.inArray;
+            },
+            _ => self.inEntry = false,
+        }
+    }
+}
+
+/// The value of key in a dictionary: the tokens of an array, or a single token (or the 3 of an indirect reference).
+fn entry(data: &[u8], key: &[u8]) -> Vec<Token> {
+    let mut tokens = lex(data, 0);
+    let mut depth = 0;
+    while let Some((_, token)) = tokens.next() {
+        match token {
+            Token::Dictionary(open) | Token::Array(open) => depth += if open { 1 } else { -1 },
+            Token::Name(ref name) if depth == 1 && &name[..] == key => {
+                let first = match tokens.next() {
+                    Some((_, first)) => first,
+                    None => return Vec::new(),
+                };
+                if first == Token::Array(true) {
+                    return tokens.map(|(_, token)| token).take_while(|token| *token != Token::Array(false)).collect();
+                }
+                let rest: Vec<Token> = tokens.map(|(_, token)| token).take(2).collect();
+                if rest.len() == 2 && integer(data, &first).is_some() && integer(data, &rest[0]).is_some() && isWord(data, &rest[1], b"R") {
+                    return vec![first, rest[0].clone(), rest[1].clone()];
+                }
+                return vec![first];
+            },
+            _ => {},
+        }
+    }
+    return Vec::new();
+}
+
+fn entryName(data: &[u8], key: &[u8]) -> Option<Vec<u8>> {
+    return match entry(data, key).into_iter().next() {
+        Some(Token::Name(name)) => Some(name),
+        _ => None,
+    };
+}
+
+fn entryInteger(data: &[u8], key: &[u8]) -> Option<u64> {
+    let value = entry(data, key);
+    return if value.len() == 1 { integer(data, &value[0]) } else { None }; //not an indirect reference
+}
+
+fn ascii85(data: &[u8]) -> Vec<u8> {
+    let mut decoded: Vec<u8> = Vec::new();
+    let mut group: Vec<u32> = Vec::new();
+    for &byte in data.iter().take_while(|&&byte| byte != b'~') { //~> ends the data
+        if byte == b'z' && group.len() == 0 {
+            decoded.extend_from_slice(&[0, 0, 0, 0]);
+        }
+        else if byte >= b'!' && byte <= b'u' {
+            group.push((byte - b'!') as u32);
+            if group.len() == 5 {
+                let value = group.iter().fold(0u32, |value, &digit| value.wrapping_mul(85).wrapping_add(digit));
+                decoded.extend_from_slice(&[(value >> 24) as u8, (value >> 16) as u8, (value >> 8) as u8, value as u8]);
+                group.clear();
+            }
+        }
+    }
+    if group.len() > 1 { //a partial group is padded with u, and gives one byte less than it has digits
+        let count = group.len() - 1;
+        group.resize(5, 84);
+        let value = group.iter().fold(0u32, |value, &digit| value.wrapping_mul(85).wrapping_add(digit));
+        decoded.extend_from_slice(&[(value >> 24) as u8, (value >> 16) as u8, (value >> 8) as u8, value as u8][..count]);
+    }
+    return decoded;
+}
+
+/// Undoes the filters of a stream, in order, decompressing up to limit bytes. None if one of them isn't supported (like
+/// the image filters, which don't hold strings anyway).
+fn decodeStream(data: &[u8], filters: &[Vec<u8>], limit: u64) -> Option<Vec<u8>> {
+    let mut decoded = data.to_vec();
+    for filter in filters {
+        decoded = match &filter[..] {
+            b"FlateDecode" | b"Fl" => {
+                let mut inflated: Vec<u8> = Vec::new();
+                let _ = ZlibDecoder::new(&decoded[..]).take(limit).read_to_end(&mut inflated); //streams that go bad part way are common, what came before is kept
+                inflated
+            },
+            b"ASCIIHexDecode" | b"AHx" => decodeHex(&decoded[..decoded.iter().position(|&byte| byte == b'>').unwrap_or(decoded.len())]),
+            b"ASCII85Decode" | b"A85" => ascii85(&decoded),
+            _ => return None,
+        };
+    }
+    return Some(decoded);
+}
+
+/// The strings in PDF syntax (as in object streams and content streams) from start on, with the strings that make up an
+/// array joined when join is set, as a TJ operator in a content stream shows the pieces of a line of text.
+fn syntaxStrings(data: &[u8], start: usize, join: bool, contents: &mut Contents) -> Vec<PdfString> {
+    let mut strings: Vec<PdfString> = Vec::new();
+    let mut joined: Option<PdfString> = None;
+    let mut depth = 0;
+    for (_, token) in lex(data, start) {
+        contents.see(data, &token);
+        match token {
+            Token::Array(open) => {
+                depth += if open { 1 } else { -1 };
+                if let Some(string) = joined.take() {
+                    strings.push(string);
+                }
+            },
+            Token::Literal(..) | Token::Hex(..) => {
+                let string = stringBytes(data, &token).unwrap();
+                if !join || depth != 1 {
+                    strings.push(string);
+                }
+                else if let Some(ref mut piece) = joined {
+                    piece.end = string.end;
+                    piece.bytes.extend(string.bytes);
+                }
+                else {
+                    joined = Some(string);
+                }
+            },
+            Token::Word(wordStart, wordEnd) if joined.is_some() => {
+                let gap: f64 = str::from_utf8(&data[wordStart..wordEnd]).ok().and_then(|gap| gap.parse().ok()).unwrap_or(0.0);
+                if gap <= SPACE_KERNING {
+                    joined.as_mut().unwrap().bytes.push(b' ');
+                }
+            },
+            _ => {},
+        }
+    }
+    strings.extend(joined);
+    return strings;
+}
+
+struct Object {
+    number: u64,
+    dictionary: (usize, usize),     //where what comes before the stream (its dictionary) is in the file
+    stream: (usize, usize),         //and where the stream's data is
+}
+
+enum Decoded { //an object's stream
+    Plain,              //without filters
+    Filtered(Vec<u8>),  //with its filters undone
+    Undecoded,          //left as it is, as the budget for decompressing is used up
+}
+
+fn decodeObject(data: &[u8], object: &Object, budget: &Cell<u64>) -> Option<Decoded> { //None if the stream's filters aren't supported
+    let dictionary = &data[object.dictionary.0..object.dictionary.1];
+    let filters: Vec<Vec<u8>> = entry(dictionary, b"Filter").into_iter().filter_map(|token| match token { Token::Name(name) => Some(name), _ => None }).collect();
+    let raw = &data[object.stream.0..object.stream.1];
+    if filters.len() == 0 {
+        return Some(Decoded::Plain);
+    }
+    if budget.get() == 0 {
+        return decodeStream(raw, &filters, 0).map(|_| Decoded::Undecoded); //still None for the filters that aren't supported
+    }
+    let stream = decodeStream(raw, &filters, budget.get())?;
+    budget.set(budget.get().saturating_sub(stream.len() as u64));
+    return Some(Decoded::Filtered(stream));
+}
+
+/// Decodes the strings in a PDF: the literal and hex strings of its objects, and those in its streams once they are
+/// decompressed (FlateDecode, ASCIIHexDecode and ASCII85Decode), including the objects packed in object streams and
+/// the text shown by content streams (with the pieces of a TJ array joined). The streams that aren't in PDF syntax (like
+/// JavaScript, fonts or embedded files) are searched with scanner instead. What the streams decode to is taken from
+/// budget, and once it is used up the streams left are searched as they are. Each string is tagged with its object as
+/// `obj N`; those from filtered streams have the offset of the stream's data as `FoundString::stream` and offsets into
+/// the decoded data. Returns the strings with at least minLength characters sorted by where they are in the file, or
+/// None if data isn't a PDF. Encrypted PDFs aren't decrypted.
+pub fn pdfStrings(data: &[u8], scanner: &Scanner, budget: &Cell<u64>) -> Option<Vec<FoundString>> {
+    if !isPdf(data) {
+        return None;
+    }
+    let minLength = scanner.options().getMinLength();
+    let mut strings: Vec<FoundString> = Vec::new();
+    let found = |string: PdfString, object: Option<u64>, stream: Option<u64>, base: usize| -> Option<FoundString> {
+        let (text, encoding) = decodeText(&string.bytes);
+        if text.chars().count() < minLength || text.trim().len() == 0 || text.chars().any(|c| c.is_control() && c != '\n' && c != '\r' && c != '\t') {
+            return None;
+        }
+        return Some(FoundString {
+            offset: (base + string.start) as u64,
+            length: string.end - string.start,
+            encoding: encoding,
+            text: text,
+            section: object.map(|object| format!("obj {}", object)),
+            address: None,
+            member: None,
+            stream: stream,
+        });
+    };
+    let mut contents = Contents::default();
+    let mut objects: Vec<Object> = Vec::new();
+    let mut current: Option<(u64, usize)> = None; //the object being read and where its body starts
+    let mut previous: [Option<u64>; 2] = [None, None]; //the last two tokens if they are integers, to spot "12 0 obj"
+    let mut tokens = lex(data, 0);
+    while let Some((start, token)) = tokens.next() {
+        contents.see(data, &token);
+        if isWord(data, &token, b"obj") && previous[0].is_some() && previous[1].is_some() {
+            current = Some((previous[0].unwrap(), tokens.position));
+        }
+        else if isWord(data, &token, b"endobj") {
+            current = None;
+        }
+        else if isWord(data, &token, b"stream") && current.is_some() {
+            let (number, body) = current.take().unwrap();
+            let mut streamStart = tokens.position;
+            streamStart += if data[streamStart..].starts_with(b"\r\n") { 2 } else if data[streamStart..].starts_with(b"\n") || data[streamStart..].starts_with(b"\r") { 1 } else { 0 };
+            let length = entryInteger(&data[body..start], b"Length").map(|length| cmp::min(length as usize, data.len() - streamStart));
+            let end = match length {
+                Some(length) if data[streamStart + length..].iter().skip_while(|&&byte| isWhitespace(byte)).take(9).eq(b"endstream".iter()) => streamStart + length,
+                _ => { //the length is an indirect reference or wrong, so the stream ends at endstream
+                    let end = data[streamStart..].windows(9).position(|window| window == b"endstream").map_or(data.len(), |end| streamStart + end);
+                    let trimmed = if data[..end].ends_with(b"\r\n") { end - 2 } else if data[..end].ends_with(b"\n") || data[..end].ends_with(b"\r") { end - 1 } else { end };
+                    cmp::max(trimmed, streamStart) //an empty stream
+                },
+            };
+            objects.push(Object { number: number, dictionary: (body, start), stream: (streamStart, end) });
+            tokens.position = end;
+        }
+        else if let Some(string) = stringBytes(data, &token) {
+            strings.extend(found(string, current.map(|(number, _)| number), None, 0));
+        }
+        previous = [previous[1], integer(data, &token)];
+    }
+    let search = |bytes: &[u8], base: usize, streamOffset: Option<u64>, number: u64| { //streams that aren't PDF syntax, with the scanner
+        return scanner.scan(bytes).map(|found| FoundString { offset: found.offset + base as u64, section: Some(format!("obj {}", number)), stream: streamOffset, ..found }).collect::<Vec<FoundString>>();
+    };
+    let isObjectStream = |object: &Object| entryName(&data[object.dictionary.0..object.dictionary.1], b"Type").is_some_and(|name| name == b"ObjStm");
+    for object in objects.iter().filter(|object| isObjectStream(object)) { //first, as the pages naming content streams are often packed in them
+        let decoded = decodeObject(data, object, budget);
+        let (bytes, base, streamOffset) = match decoded {
+            Some(Decoded::Plain) => (&data[object.stream.0..object.stream.1], object.stream.0, None),
+            Some(Decoded::Filtered(ref stream)) => (&stream[..], 0, Some(object.stream.0 as u64)),
+            Some(Decoded::Undecoded) => {
+                strings.extend(search(&data[object.stream.0..object.stream.1], object.stream.0, None, object.number));
+                continue;
+            },
+            None => continue,
+        };
+        let first = cmp::min(entryInteger(&data[object.dictionary.0..object.dictionary.1], b"First").unwrap_or(0) as usize, bytes.len());
+        let numbers: Vec<u64> = lex(&bytes[..first], 0).filter_map(|(_, token)| integer(&bytes[..first], &token)).collect();
+        let mut packed: Vec<(usize, u64)> = numbers.chunks(2).filter(|pair| pair.len() == 2).filter_map(|pair| Some((first.checked_add(pair[1] as usize)?, pair[0]))).collect(); //where each object starts, and its number
+        packed.sort();
+        for string in syntaxStrings(bytes, first, false, &mut contents) {
+            let number = packed.iter().rev().find(|&&(start, _)| start <= string.start).map(|&(_, number)| number);
+            strings.extend(found(string, number, streamOffset, base));
+        }
+    }
+    for object in objects.iter().filter(|object| !isObjectStream(object)) {
+        let dictionary = &data[object.dictionary.0..object.dictionary.1];
+        let decoded = decodeObject(data, object, budget);
+        let (bytes, base, streamOffset) = match decoded {
+            Some(Decoded::Plain) => (&data[object.stream.0..object.stream.1], object.stream.0, None),
+            Some(Decoded::Filtered(ref stream)) => (&stream[..], 0, Some(object.stream.0 as u64)),
+            Some(Decoded::Undecoded) => {
+                strings.extend(search(&data[object.stream.0..object.stream.1], object.stream.0, None, object.number));
+                continue;
+            },
+            None => continue,
+        };
+        if contents.objects.contains(&object.number) || entryName(dictionary, b"Subtype").is_some_and(|name| name == b"Form") { //content streams, of pages or of forms
+            for string in syntaxStrings(bytes, 0, true, &mut Contents::default()) {
+                strings.extend(found(string, Some(object.number), streamOffset, base));
+            }
+        }
+        else if entryName(dictionary, b"Type").is_none_or(|name| name != b"XRef") {
+            strings.extend(search(bytes, base, streamOffset, object.number));
+        }
+    }
+    strings.sort_by_key(|found| (found.stream.unwrap_or(found.offset), found.offset));
+    return Some(strings);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use encoding::Encoding;
+    use scanner::{ScanOptions, Scanner};
+    use testing::readFile;
+    use super::{decodeLiteral, decodeHex, ascii85, pdfStrings};
+
+    #[test]
+    fn testDecodeStrings() {
+        assert_eq!(b"a (nested) \"string\"\n!\tend".to_vec(), decodeLiteral(b"a (nested) \\\"string\\\"\r\\041\\tend"));
+        assert_eq!(b"continued\x08A".to_vec(), decodeLiteral(b"contin\\\r\nued\\b\\101"));
+        assert_eq!(b"Hi!\xa0".to_vec(), decodeHex(b"48 69\n21a"));
+        assert_eq!(b"Man is ".to_vec(), ascii85(b"9jqo^BlbD~>ignored"));
+    }
+
+    #[test]
+    fn testPdfStrings() {
+        let data = readFile("./testCases/report.pdf");
+        let scanner = Scanner::new(ScanOptions::new().nullTerminated(false));
+        let budget = Cell::new(1 << 20);
+        let strings = pdfStrings(&data, &scanner, &budget).unwrap();
+        let found: Vec<(Option<u64>, u64, &str, &str)> = strings.iter().map(|found| (found.stream, found.offset, found.section.as_ref().unwrap().as_ref(), found.text.as_ref())).collect();
+        assert_eq!(vec![(Some(195), 25, "obj 4", "Quarterly results"), (Some(195), 71, "obj 4", "Internal only"), //a content stream, with the pieces of the TJ array joined
+                (Some(400), 64, "obj 3", "Packed annotation text"), (Some(400), 125, "obj 8", "Open (action) handler"), //objects packed in an object stream
+                (None, 588, "obj 7", "Quarterly (draft) report!"), (None, 629, "obj 7", "Rosa Cartaía"),
+                (Some(784), 0, "obj 9", "var payload = \"this.exportDataObject\";"), (Some(784), 39, "obj 9", "app.alert(\"Malicious script ran\");")], found); //JavaScript, searched
+        assert_eq!(Encoding::BigEndian16, strings[5].encoding);
+        assert!(budget.get() < 1 << 20);
+        let undecoded = pdfStrings(&data, &scanner, &Cell::new(0)).unwrap(); //the streams are searched as they are
+        assert!(undecoded.iter().all(|found| found.stream.is_none()));
+        assert!(undecoded.iter().any(|found| found.section == Some(String::from("obj 4")) && found.offset >= 195 && found.offset < 300));
+        assert!(undecoded.iter().any(|found| found.text == "Quarterly (draft) report!"));
+        assert_eq!(None, pdfStrings(b"\x7fELF not a PDF", &Scanner::new(ScanOptions::new()), &Cell::new(1 << 20)));
+    }
+}
@@ -0,0 +1,409 @@
+use std::collections::HashSet;
+
+use encoding::Encoding;
+use scanner::FoundString;
+use sections::{Section, readUInt, readCString};
+
+const SCN_CNT_CODE: u64 = 0x20;
+const SCN_CNT_INITIALIZED_DATA: u64 = 0x40;
+const SCN_MEM_EXECUTE: u64 = 0x20000000;
+const RT_STRING: u64 = 6;      //string tables, in blocks of 16 strings
+const RT_VERSION: u64 = 16;    //the version info shown in the file's properties
+const RESOURCE_DIRECTORY: u64 = 2; //the index of the resource directory in the optional header's data directories
+const CLR_DIRECTORY: u64 = 14;     //the CLI header of .NET assemblies
+const METADATA_SIGNATURE: u64 = 0x424a5342; //BSJB
+
+struct Headers { //the parts of the headers needed to find the sections and resources
+    ptrSize: usize,     //4 for PE32, 8 for PE32+
+    sections: u64,      //where the section table starts
+    count: u64,         //the number of sections
+    imageBase: u64,
+    resources: Option<(u64, u64)>, //the RVA and size of the resource directory
+    clr: Option<(u64, u64)>,       //the RVA and size of the CLI header
+    symbols: u64,       //where the COFF string table (the long section names in object files) starts, 0 if there is none
+}
+
+fn parseHeaders(data: &[u8]) -> Option<Headers> {
+    if data.len() < 0x40 || &data[0..2] != b"MZ" {
+        return None;
+    }
+    let read = |offset: u64, size: usize| readUInt(data, offset, size, false);
+    let pe = read(0x3C, 4)?;
+    if read(pe, 4)? != 0x4550 { //PE\0\0
+        return None;
+    }
+    let coff = pe + 4;
+    let optional = coff + 20;
+    let optionalSize = read(coff + 16, 2)?;
+    let (ptrSize, imageBase, directories, directoryCount) = match read(optional, 2)? {
+        0x10b => (4, read(optional + 28, 4)?, optional + 96, read(optional + 92, 4)?),     //PE32
+        0x20b => (8, read(optional + 24, 8)?, optional + 112, read(optional + 108, 4)?),   //PE32+
+        _ => return None,
+    };
+    let directory = |index: u64| { //the RVA and size of a data directory, None if it is missing or empty
+        if directoryCount <= index {
+            return None;
+        }
+        return match (read(directories + index * 8, 4), read(directories + index * 8 + 4, 4)) {
+            (Some(rva), Some(size)) if rva != 0 && size != 0 => Some((rva, size)),
+            _ => None,
+        };
+    };
+    let symbolTable = read(coff + 8, 4)?;
+    return Some(Headers {
+        ptrSize: ptrSize,
+        sections: optional + optionalSize,
+        count: read(coff + 2, 2)?,
+        imageBase: imageBase,
+        resources: directory(RESOURCE_DIRECTORY),
+        clr: directory(CLR_DIRECTORY),
+        symbols: if symbolTable == 0 { 0 } else { symbolTable + read(coff + 12, 4)? * 18 }, //symbols are 18 bytes each
+    });
+}
+
+/// Parses the section table of a PE file (an .exe, .dll, .sys...). Returns None if data isn't a PE file.
+pub fn parseSections(data: &[u8]) -> Option<Vec<Section>> {
+    let headers = parseHeaders(data)?;
+    let read = |offset: u64, size: usize| readUInt(data, offset, size, false);
+    let mut sections: Vec<Section> = Vec::new();
+    for index in 0..headers.count {
+        let header = headers.sections + index * 40;
+        if header + 40 > data.len() as u64 {
+            break;
+        }
+        let rawName = &data[header as usize..header as usize + 8];
+        let rawName = &rawName[..rawName.iter().position(|byte| *byte == 0).unwrap_or(8)];
+        let mut name = String::from_utf8_lossy(rawName).into_owned();
+        if name.starts_with('/') && headers.symbols != 0 { //long names are kept in the string table, /4 is the name at offset 4
+            if let Ok(offset) = name[1..].parse::<u64>() {
+                name = readCString(data, headers.symbols + offset).unwrap_or(name);
+            }
+        }
+        let characteristics = read(header + 36, 4)?;
+        sections.push(Section {
+            name: name,
+            offset: read(header + 20, 4)?,
+            size: read(header + 16, 4)?,
+            address: headers.imageBase.checked_add(read(header + 12, 4)?), //None if it is past the end of the address space
+            imageBase: headers.imageBase,
+            isData: characteristics & SCN_CNT_INITIALIZED_DATA != 0 && characteristics & (SCN_CNT_CODE | SCN_MEM_EXECUTE) == 0,
+        });
+    }
+    return Some(sections);
+}
+
+pub fn pointerSize(data: &[u8]) -> Option<usize> {
+    return parseHeaders(data).map(|headers| headers.ptrSize);
+}
+
+fn rvaToOffset(sections: &[Section], rva: u64) -> Option<u64> { //the place in the file the data at rva is loaded from
+    for section in sections.iter() {
+        let start = section.address? - section.imageBase;
+        if rva >= start && rva < start + section.size {
+            return Some(section.offset + (rva - start));
+        }
+    }
+    return None;
+}
+
+/// Decodes the strings in the resource directory: the string tables and the text values of the version info. Returns the
+/// file offset of the resource directory and the strings (with at least minLength characters) sorted by offset, or None
+/// if data isn't a PE file with resources.
+pub fn resourceStrings(data: &[u8], minLength: usize) -> Option<(u64, Vec<FoundString>)> {
+    let headers = parseHeaders(data)?;
+    let (rva, _) = headers.resources?;
+    let sections = parseSections(data)?;
+    let root = rvaToOffset(&sections, rva)?;
+    let mut strings: Vec<FoundString> = Vec::new();
+    let mut visited: HashSet<u64> = HashSet::new(); //the directories already read, as broken files can point many entries at the same one
+    for (typeId, types) in directoryEntries(data, root, root, &mut visited) { //the tree is type, then name (or id), then language
+        if (typeId != RT_STRING && typeId != RT_VERSION) || !isDirectory(types) {
+            continue;
+        }
+        for (nameId, names) in directoryEntries(data, root, types, &mut visited) {
+            if !isDirectory(names) {
+                continue;
+            }
+            for (_, languages) in directoryEntries(data, root, names, &mut visited) {
+                let entry = match (isDirectory(languages), readUInt(data, languages, 4, false), readUInt(data, languages + 4, 4, false)) {
+                    (false, Some(entryRva), Some(size)) => (rvaToOffset(&sections, entryRva), size),
+                    _ => continue,
+                };
+                match entry {
+                    (Some(offset), size) if typeId == RT_STRING => stringTable(data, offset, offset + size, nameId, &mut strings),
+                    (Some(offset), size) => versionBlock(data, offset, offset + size, 0, &mut strings),
+                    _ => {},
+                }
+            }
+        }
+    }
+    strings.retain(|found| found.text.chars().count() >= minLength);
+    strings.sort_by_key(|found| found.offset);
+    strings.dedup_by_key(|found| found.offset);
+    for found in strings.iter_mut() {
+        found.address = headers.imageBase.checked_add(rva + (found.offset - root));
+    }
+    return Some((root, strings));
+}
+
+/// Reads the metadata of a .NET assembly and decodes its `#Strings` heap (the names of types, methods, fields... as
+/// UTF-8) and its `#US` heap (the string literals of the code, as UTF-16), tagging each string with its heap. Returns the
+/// file offsets each heap spans with its strings (those with at least minLength characters, sorted by offset), or None if
+/// data isn't a .NET assembly.
+pub fn metadataStrings(data: &[u8], minLength: usize) -> Option<Vec<((u64, u64), Vec<FoundString>)>> {
+    let headers = parseHeaders(data)?;
+    let (clr, _) = headers.clr?;
+    let sections = parseSections(data)?;
+    let read = |offset: u64, size: usize| readUInt(data, offset, size, false);
+    let metadataRva = read(rvaToOffset(&sections, clr)? + 8, 4)?;
+    let root = rvaToOffset(&sections, metadataRva)?;
+    if read(root, 4)? != METADATA_SIGNATURE {
+        return None;
+    }
+    let versionLength = read(root + 12, 4)?;
+    let mut header = root + 16 + versionLength + 2; //past the version string and the flags
+    let count = read(header, 2)?;
+    header += 2;
+    let mut heaps: Vec<((u64, u64), Vec<FoundString>)> = Vec::new();
+    for _ in 0..count {
+        let (offset, size) = (root + read(header, 4)?, read(header + 4, 4)?);
+        let name = readCString(data, header + 8)?;
+        header = align4(header + 8 + name.len() as u64 + 1);
+        let end = if offset + size > data.len() as u64 { data.len() as u64 } else { offset + size };
+        let mut strings = match &name[..] {
+            "#Strings" => identifiers(data, offset, end),
+            "#US" => userStrings(data, offset, end),
+            _ => continue,
+        };
+        strings.retain(|found| found.text.chars().count() >= minLength);
+        for found in strings.iter_mut() {
+            found.address = headers.imageBase.checked_add(metadataRva + (found.offset - root));
+        }
+        heaps.push(((offset, end), strings));
+    }
+    return Some(heaps);
+}
+
+fn identifiers(data: &[u8], start: u64, end: u64) -> Vec<FoundString> { //the #Strings heap, null terminated UTF-8 strings
+    let mut strings: Vec<FoundString> = Vec::new();
+    let mut offset = start;
+    while offset < end {
+        let length = data[offset as usize..end as usize].iter().position(|byte| *byte == 0).unwrap_or((end - offset) as usize);
+        if length > 0 {
+            strings.push(FoundString {
+                offset: offset,
+                length: length,
+                encoding: Encoding::SevenBit,
+                text: String::from_utf8_lossy(&data[offset as usize..offset as usize + length]).into_owned(),
+                section: Some(String::from("#Strings")),
+                address: None,
+                member: None,
+                stream: None,
+            });
+        }
+        offset += length as u64 + 1;
+    }
+    return strings;
+}
+
+fn userStrings(data: &[u8], start: u64, end: u64) -> Vec<FoundString> { //the #US heap, UTF-16 strings prefixed by their length in bytes (plus a byte of flags)
+    let mut strings: Vec<FoundString> = Vec::new();
+    let mut offset = start;
+    while offset < end {
+        let first = data[offset as usize] as u64;
+        let (size, prefix) = match first { //the length is compressed into 1, 2 or 4 bytes
+            _ if first & 0x80 == 0 => (first, 1),
+            _ if first & 0xc0 == 0x80 => match readUInt(data, offset, 2, true) {
+                Some(size) => (size & 0x3fff, 2),
+                None => break,
+            },
+            _ => match readUInt(data, offset, 4, true) {
+                Some(size) => (size & 0x1fffffff, 4),
+                None => break,
+            },
+        };
+        let units = size / 2;
+        if offset + prefix + size > end {
+            break;
+        }
+        if units > 0 {
+            strings.push(resourceString(offset + prefix, units, utf16(data, offset + prefix, units), String::from("#US")));
+        }
+        offset += prefix + size;
+    }
+    return strings;
+}
+
+fn isDirectory(entry: u64) -> bool {
+    return entry & 0x80000000 != 0;
+}
+
+fn directoryEntries(data: &[u8], root: u64, directory: u64, visited: &mut HashSet<u64>) -> Vec<(u64, u64)> { //the ids of the entries of a resource directory and the file offsets of what they point to (subdirectories have the high bit set), leaving out the subdirectories in visited
+    let mut entries: Vec<(u64, u64)> = Vec::new();
+    let read = |offset: u64, size: usize| readUInt(data, offset, size, false);
+    let directory = directory & 0x7fffffff;
+    let count = match (read(directory + 12, 2), read(directory + 14, 2)) {
+        (Some(named), Some(ids)) => named + ids,
+        _ => return entries,
+    };
+    for index in 0..count {
+        let entry = directory + 16 + index * 8;
+        let (id, target) = match (read(entry, 4), read(entry + 4, 4)) {
+            (Some(id), Some(target)) => (id, target),
+            _ => break,
+        };
+        let offset = root + (target & 0x7fffffff);
+        if offset <= directory || (isDirectory(target) && !visited.insert(offset)) { //entries only point forward, this stops loops in broken files
+            continue;
+        }
+        entries.push((id, if isDirectory(target) { offset | 0x80000000 } else { offset }));
+    }
+    return entries;
+}
+
+fn utf16(data: &[u8], offset: u64, units: u64) -> String { //decodes units UTF-16LE code units at offset
+    let mut decoded: Vec<u16> = Vec::new();
+    for index in 0..units {
+        match readUInt(data, offset + index * 2, 2, false) {
+            Some(unit) => decoded.push(unit as u16),
+            None => break,
+        }
+    }
+    return String::from_utf16_lossy(&decoded);
+}
+
+fn resourceString(offset: u64, units: u64, text: String, section: String) -> FoundString {
+    return FoundString {
+        offset: offset,
+        length: units as usize * 2,
+        encoding: Encoding::LittleEndian16,
+        text: text,
+        section: Some(section),
+        address: None,
+        member: None,
+        stream: None,
+    };
+}
+
+fn stringTable(data: &[u8], mut offset: u64, end: u64, block: u64, strings: &mut Vec<FoundString>) { //a block of 16 length prefixed strings, block n holds the strings with ids (n-1)*16 to n*16-1
+    for index in 0..16 {
+        let units = match readUInt(data, offset, 2, false) {
+            Some(units) if offset + 2 + units * 2 <= end => units,
+            _ => return,
+        };
+        if units > 0 {
+            let text = utf16(data, offset + 2, units);
+            strings.push(resourceString(offset + 2, units, text, format!(".rsrc:string:{}", block.saturating_sub(1) * 16 + index)));
+        }
+        offset += 2 + units * 2;
+    }
+}
+
+fn align4(offset: u64) -> u64 {
+    return (offset + 3) & !3;
+}
+
+fn versionBlock(data: &[u8], start: u64, end: u64, depth: usize, strings: &mut Vec<FoundString>) { //the version info is a tree of blocks, each with a key, a value and children
+    let read = |offset: u64| readUInt(data, offset, 2, false);
+    let (length, valueLength, valueType) = match (read(start), read(start + 2), read(start + 4)) {
+        (Some(length), Some(valueLength), Some(valueType)) => (length, valueLength, valueType),
+        _ => return,
+    };
+    let end = if start + length < end { start + length } else { end };
+    let mut keyEnd = start + 6;
+    while keyEnd + 2 <= end && read(keyEnd) != Some(0) {
+        keyEnd += 2;
+    }
+    let key = utf16(data, start + 6, (keyEnd - start - 6) / 2);
+    let value = align4(keyEnd + 2);
+    let mut children: u64;
+    if valueType == 1 { //text, the length is in characters (though some compilers write bytes) and includes the null
+        let mut units = 0;
+        while units < valueLength && value + units * 2 + 2 <= end && read(value + units * 2) != Some(0) {
+            units += 1;
+        }
+        if units > 0 {
+            strings.push(resourceString(value, units, utf16(data, value, units), format!(".rsrc:version:{}", key)));
+        }
+        children = align4(value + valueLength * 2);
+    }
+    else {
+        children = align4(value + valueLength);
+    }
+    if depth > 8 { //real version info is 4 deep
+        return;
+    }
+    while children + 6 <= end {
+        let childLength = match read(children) {
+            Some(childLength) if childLength > 0 => childLength,
+            _ => return,
+        };
+        versionBlock(data, children, end, depth + 1, strings);
+        children = align4(children + childLength);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use sections::readUInt;
+    use testing::readFile;
+    use super::{parseSections, resourceStrings, metadataStrings, directoryEntries};
+
+    #[test]
+    fn testParseSections() {
+        let data = readFile("./testCases/hello.exe");
+        let sections: Vec<(String, u64, Option<u64>, bool)> = parseSections(&data).unwrap().into_iter().map(|section| (section.name, section.offset, section.address, section.isData)).collect();
+        assert_eq!(vec![(String::from(".text"), 0x200, Some(0x140001000), false), (String::from(".rdata"), 0x400, Some(0x140002000), true), (String::from(".rsrc"), 0x600, Some(0x140003000), true)], sections);
+        assert_eq!(None, parseSections(&readFile("./testCases/a.out")));
+        assert_eq!(None, parseSections(&data[0..0x100])); //the headers are cut off
+        let mut patched = data.clone();
+        let imageBase = readUInt(&data, 0x3C, 4, false).unwrap() as usize + 24 + 24;
+        patched[imageBase..imageBase + 8].copy_from_slice(b"\0\xe0\xff\xff\xff\xff\xff\xff"); //only .text is loaded before the end of the address space
+        let addresses: Vec<Option<u64>> = parseSections(&patched).unwrap().into_iter().map(|section| section.address).collect();
+        assert_eq!(vec![Some(0xfffffffffffff000), None, None], addresses);
+        assert_eq!(None, resourceStrings(&patched, 4));
+    }
+
+    #[test]
+    fn testResourceStrings() {
+        let data = readFile("./testCases/hello.exe");
+        let (root, strings) = resourceStrings(&data, 4).unwrap();
+        assert_eq!(0x600, root);
+        let found: Vec<(String, String)> = strings.iter().map(|found| (found.section.clone().unwrap(), found.text.clone())).collect();
+        assert_eq!(vec![
+            (String::from(".rsrc:string:1"), String::from("Resource string one")),
+            (String::from(".rsrc:string:2"), String::from("Second")),
+            (String::from(".rsrc:version:CompanyName"), String::from("Example Corp")),
+            (String::from(".rsrc:version:FileDescription"), String::from("RStrings test binary")),
+            (String::from(".rsrc:version:ProductVersion"), String::from("1.2.3")),
+        ], found);
+        assert_eq!((0x6a4, Some(0x1400030a4), 38), (strings[0].offset, strings[0].address, strings[0].length));
+        assert_eq!(4, resourceStrings(&data, 6).unwrap().1.len());
+        assert_eq!(None, resourceStrings(&readFile("./testCases/a.out"), 4));
+
+        let shared = b"\0\0\0\0\0\0\0\0\0\0\0\0\0\0\x02\0\x01\0\0\0\x20\0\0\x80\x02\0\0\0\x20\0\0\x80"; //two entries pointing at the same subdirectory
+        assert_eq!(vec![(1, 0x80000020)], directoryEntries(shared, 0, 0, &mut HashSet::new()));
+    }
+
+    #[test]
+    fn testMetadataStrings() {
+        let data = readFile("./testCases/hello.dll");
+        let heaps = metadataStrings(&data, 4).unwrap();
+        assert_eq!(vec![(0x2b4, 0x2f8), (0x2f8, 0x33c)], heaps.iter().map(|&(span, _)| span).collect::<Vec<(u64, u64)>>());
+        let found: Vec<(String, String)> = heaps.iter().flat_map(|&(_, ref strings)| strings.iter()).map(|found| (found.section.clone().unwrap(), found.text.clone())).collect();
+        assert_eq!(vec![
+            (String::from("#Strings"), String::from("<Module>")),
+            (String::from("#Strings"), String::from("hello.dll")),
+            (String::from("#Strings"), String::from("Program")),
+            (String::from("#Strings"), String::from("Main")),
+            (String::from("#Strings"), String::from("System.Console")),
+            (String::from("#Strings"), String::from("WriteLine")),
+            (String::from("#Strings"), String::from("mscorlib")),
+            (String::from("#US"), String::from("Hello from .NET")),
+            (String::from("#US"), String::from("Wide literal")),
+        ], found);
+        assert_eq!((0x2fa, Some(0x100020fa), 30), (heaps[1].1[0].offset, heaps[1].1[0].address, heaps[1].1[0].length));
+        assert_eq!(None, metadataStrings(&readFile("./testCases/hello.exe"), 4));
+    }
+}
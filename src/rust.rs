@@ -0,0 +1,84 @@
+use encoding::Encoding;
+use scanner::{ScanOptions, FoundString};
+use sections::{Section, readUInt, readUtf8, addressToOffset, pointedStrings, pointerLayout};
+
+const MARKERS: [&'static [u8]; 3] = [b"/rustc/", b"RUST_BACKTRACE", b"rust_panic"]; //every binary linking the standard library has these
+const MAX_LINE: u64 = 1 << 24;
+const MAX_COLUMN: u64 = 1 << 16;
+
+fn isRust(data: &[u8], sections: &[Section]) -> bool {
+    if sections.iter().any(|section| section.name == ".rustc") { //the metadata of dylibs and proc macros
+        return true;
+    }
+    return MARKERS.iter().any(|marker| data.windows(marker.len()).any(|window| window == *marker));
+}
+
+fn panicLocations(data: &[u8], sections: &[Section], ptrSize: usize, bigEndian: bool) -> Vec<FoundString> { //the core::panic::Location structs: the file (a &str), then the line and column as 32 bit integers
+    let mut locations: Vec<FoundString> = Vec::new();
+    let size = ptrSize as u64;
+    let read = |offset: u64, size: usize| readUInt(data, offset, size, bigEndian);
+    for section in sections.iter().filter(|section| section.isData && section.address.is_some()) {
+        let mut location = section.offset + (size - section.address.unwrap() % size) % size;
+        while location + 2 * size + 8 <= section.offset + section.size {
+            let file = match (read(location, ptrSize), read(location + size, ptrSize)) {
+                (Some(address), Some(length)) => addressToOffset(sections, address).and_then(|offset| readUtf8(data, offset, length)),
+                _ => None,
+            };
+            let position = (read(location + 2 * size, 4), read(location + 2 * size + 4, 4));
+            match (file, position) {
+                (Some(file), (Some(line), Some(column))) if file.text.ends_with(".rs") && line > 0 && line < MAX_LINE && column > 0 && column < MAX_COLUMN => {
+                    locations.push(FoundString {
+                        offset: location,
+                        length: 2 * ptrSize + 8,
+                        encoding: Encoding::SevenBit,
+                        text: format!("{}:{}:{}", file.text, line, column),
+                        section: None,
+                        address: None,
+                        member: None,
+                        stream: None,
+                    });
+                    location += 2 * size + 8;
+                },
+                _ => location += size,
+            }
+        }
+    }
+    return locations;
+}
+
+/// Finds the strings of a Rust binary: the string literals (which Rust packs together without terminators) split at the
+/// lengths given by the `&str`s (pointer and length pairs) in the data sections, and the places the binary can panic
+/// from, as `file:line:column` at the offset of their `Location` struct. Literals that are only referenced from code
+/// aren't found. Returns None if data isn't a Rust binary, otherwise the strings with at least the minimum length,
+/// sorted by offset.
+pub fn rustStrings(data: &[u8], sections: &[Section], options: &ScanOptions) -> Option<Vec<FoundString>> {
+    if !isRust(data, sections) {
+        return None;
+    }
+    let (ptrSize, bigEndian) = pointerLayout(data)?;
+    let mut strings = pointedStrings(data, sections, ptrSize, bigEndian);
+    strings.extend(panicLocations(data, sections, ptrSize, bigEndian));
+    strings.retain(|found| found.text.chars().count() >= options.getMinLength());
+    strings.sort_by_key(|found| (found.offset, found.length));
+    strings.dedup_by_key(|found| (found.offset, found.length));
+    return Some(strings);
+}
+
+#[cfg(test)]
+mod tests {
+    use scanner::ScanOptions;
+    use sections::findSections;
+    use testing::readFile;
+    use super::rustStrings;
+
+    #[test]
+    fn testRustStrings() {
+        let data = readFile("./testCases/rusthello");
+        let strings = rustStrings(&data, &findSections(&data).unwrap(), &ScanOptions::new()).unwrap();
+        let texts: Vec<&str> = strings.iter().map(|found| &found.text[..]).collect();
+        assert_eq!(vec!["Hello, Rust!", "src/main.rs", "called `Option::unwrap()` on a `None` value", "index out of bounds", "RUST_BACKTRACE",
+            "src/main.rs:12:5", "src/main.rs:30:17"], texts);
+        assert_eq!((0x2f80 + 64, 24), (strings[5].offset, strings[5].length));
+        assert_eq!(None, rustStrings(&readFile("./testCases/gohello"), &findSections(&readFile("./testCases/gohello")).unwrap(), &ScanOptions::new()));
+    }
+}
@@ -0,0 +1,818 @@
+use std::str; //to read utf-8
+use std::io; //for streaming input
+use std::io::Read;
+use std::cmp;
+use std::thread; //for concurrency
+use std::sync::mpsc; //to hand the strings each thread found back in order
+
+use encoding::Encoding;
+use encoding::{isPrintableASCII, isUTF8, isPrintableUnit, readUnit};
+
+/// The settings of a scan, built up with the builder methods:
+///
+/// ```
+/// use rstrings::{Encoding, ScanOptions};
+/// let options = ScanOptions::new().minLength(6).encodings(vec![Encoding::SevenBit, Encoding::LittleEndian16]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    minLength: usize,           //the number of printable characters needed for something to qualify as a string
+    nullTerminated: bool,       //whether strings have to be followed by a null character
+    unicode: bool,              //whether non-ASCII characters are allowed (UTF-8 for the 7-bit encoding)
+    encodings: Vec<Encoding>,   //the encodings to search for, the first one that matches at an offset wins
+    bufferSize: usize,          //the number of bytes read at a time when scanning a stream
+    resources: bool,            //whether the resources of PE files are decoded instead of scanned
+    relativeAddresses: bool,    //whether addresses are given relative to the image base (RVAs) instead of as virtual addresses
+    go: bool,                   //whether the string literals of Go binaries are split at the lengths Go gives them
+    rust: bool,                 //the same for Rust binaries
+    dotnet: bool,               //whether the string heaps of .NET assemblies are decoded
+    java: bool,                 //whether the constant pools of Java classes (on their own or in a JAR) are decoded
+    dex: bool,                  //whether the string tables of Android DEX files (on their own or in an APK) are decoded
+    dexReferences: bool,        //whether the strings of DEX files are tagged with the methods that load them
+    pdf: bool,                  //whether the objects and streams of PDFs are decoded
+    decompress: bool,           //whether compressed input (gzip, zlib, xz, bzip2, zstd or lzma) is decompressed and the result searched
+    archiveDepth: usize,        //how many levels of archives (archives in archives...) the members of are searched, 0 to search archives as they are
+    maxExtracted: u64,          //the most bytes decompressed from a file, its archives and theirs, so a zip bomb can't use up the memory
+    carve: bool,                //whether compressed streams in the middle of files (e.g. firmware images) are found and decompressed
+}
+
+const MIN_CHUNK_SIZE: usize = 4096; //chunks smaller than this aren't worth a thread
+
+const LOOKAHEAD: usize = 8; //the most bytes any of the checks look at past the end of a string (utf-8 looks at the 4 bytes after the terminator)
+
+impl ScanOptions {
+    /// The defaults match the command line: 4 characters, null terminated, 7-bit ASCII, compressed input decompressed and
+    /// archives opened 4 levels deep with up to 1GB decompressed.
+    pub fn new() -> ScanOptions {
+        return ScanOptions {
+            minLength: 4,
+            nullTerminated: true,
+            unicode: false,
+            encodings: vec![Encoding::SevenBit],
+            bufferSize: 64 * 1024,
+            resources: false,
+            relativeAddresses: false,
+            go: false,
+            rust: false,
+            dotnet: false,
+            java: false,
+            dex: false,
+            dexReferences: false,
+            pdf: false,
+            decompress: true,
+            archiveDepth: 4,
+            maxExtracted: 1 << 30,
+            carve: false,
+        };
+    }
+
+    pub fn minLength(mut self, minLength: usize) -> ScanOptions {
+        self.minLength = minLength;
+        return self;
+    }
+
+    pub fn nullTerminated(mut self, nullTerminated: bool) -> ScanOptions {
+        self.nullTerminated = nullTerminated;
+        return self;
+    }
+
+    pub fn unicode(mut self, unicode: bool) -> ScanOptions {
+        self.unicode = unicode;
+        return self;
+    }
+
+    /// Panics if `encodings` is empty.
+    pub fn encodings(mut self, encodings: Vec<Encoding>) -> ScanOptions {
+        assert!(encodings.len() > 0, "at least one encoding is needed");
+        self.encodings = encodings;
+        return self;
+    }
+
+    /// The number of bytes read at a time by `Scanner::scanReader`. The buffer only grows past this if a single string is longer.
+    pub fn bufferSize(mut self, bufferSize: usize) -> ScanOptions {
+        assert!(bufferSize > 0, "the buffer size must be at least 1 byte");
+        self.bufferSize = bufferSize;
+        return self;
+    }
+
+    /// Decode the string tables and version info in the resources of PE files (as UTF-16) instead of scanning the bytes of
+    /// the resource section. Only used by `scanSections`.
+    pub fn resources(mut self, resources: bool) -> ScanOptions {
+        self.resources = resources;
+        return self;
+    }
+
+    /// Give addresses relative to the image base (RVAs) rather than as virtual addresses. Only used by `scanSections`.
+    pub fn relativeAddresses(mut self, relativeAddresses: bool) -> ScanOptions {
+        self.relativeAddresses = relativeAddresses;
+        return self;
+    }
+
+    /// Split the string literals of Go binaries (which aren't null terminated) at their real lengths, and add the Go
+    /// version and module info from the build info. Only used by `scanSections`.
+    pub fn go(mut self, go: bool) -> ScanOptions {
+        self.go = go;
+        return self;
+    }
+
+    /// Split the string literals of Rust binaries at the lengths given by the `&str`s pointing at them, and add the
+    /// places the binary panics from. Only used by `scanSections`.
+    pub fn rust(mut self, rust: bool) -> ScanOptions {
+        self.rust = rust;
+        return self;
+    }
+
+    /// Decode the `#Strings` (identifiers) and `#US` (string literals) heaps in the metadata of .NET assemblies instead
+    /// of scanning their bytes. Only used by `scanSections`.
+    pub fn dotnet(mut self, dotnet: bool) -> ScanOptions {
+        self.dotnet = dotnet;
+        return self;
+    }
+
+    /// Decode the constant pools of Java class files, and of the classes in JARs (whose other files are decompressed and
+    /// searched), instead of searching their bytes. Only used by `scanSections`, as are the DEX options below.
+    pub fn java(mut self, java: bool) -> ScanOptions {
+        self.java = java;
+        return self;
+    }
+
+    /// Decode the string tables of Android DEX files, and of the DEX files in APKs (whose other files are decompressed and
+    /// searched), instead of searching their bytes.
+    pub fn dex(mut self, dex: bool) -> ScanOptions {
+        self.dex = dex;
+        return self;
+    }
+
+    /// Tag the strings of DEX files with the methods that load them. Finding them means going through all the code.
+    pub fn dexReferences(mut self, dexReferences: bool) -> ScanOptions {
+        self.dexReferences = dexReferences;
+        return self;
+    }
+
+    /// Decode PDFs: the literal and hex strings of their objects, and of their streams once decompressed, each tagged with
+    /// its object. The streams that aren't PDF syntax (like JavaScript) are searched instead.
+    pub fn pdf(mut self, pdf: bool) -> ScanOptions {
+        self.pdf = pdf;
+        return self;
+    }
+
+    /// Decompress input compressed with gzip, zlib, xz, bzip2, zstd or lzma (told apart by their magic bytes) and search
+    /// what it decompresses to instead of the compressed bytes. The strings found have the offset of the compressed stream
+    /// as `FoundString::stream` and offsets into the decompressed data. Only used by `scanSections`.
+    pub fn decompress(mut self, decompress: bool) -> ScanOptions {
+        self.decompress = decompress;
+        return self;
+    }
+
+    /// Search the files in archives (zip, tar, cpio, ar, and the payloads of Debian and RPM packages) as if they were
+    /// separate files, and the archives in them and so on up to archiveDepth levels deep. The strings found have their
+    /// file as `FoundString::member` (like `dir/inner.zip!file.txt` for nested archives) and offsets into it. Only used by
+    /// `scanSections`, as is the limit below.
+    pub fn archiveDepth(mut self, archiveDepth: usize) -> ScanOptions {
+        self.archiveDepth = archiveDepth;
+        return self;
+    }
+
+    /// The most bytes decompressed from a file, counting the compressed members of its archives and the compressed data
+    /// in them. Past it what's left is searched as it is, or skipped for the members of zip archives.
+    pub fn maxExtracted(mut self, maxExtracted: u64) -> ScanOptions {
+        self.maxExtracted = maxExtracted;
+        return self;
+    }
+
+    /// Look for compressed streams at every offset of files that aren't compressed or archives as a whole (like firmware
+    /// images, as binwalk does) and search what they decompress to as well as the file. The strings found in a stream have
+    /// its offset in the file as `FoundString::stream`. Counts against maxExtracted. Only used by `scanSections`.
+    pub fn carve(mut self, carve: bool) -> ScanOptions {
+        self.carve = carve;
+        return self;
+    }
+
+    pub fn getEncodings(&self) -> &[Encoding] {
+        return &self.encodings;
+    }
+
+    pub fn getMinLength(&self) -> usize {
+        return self.minLength;
+    }
+
+    pub fn getResources(&self) -> bool {
+        return self.resources;
+    }
+
+    pub fn getRelativeAddresses(&self) -> bool {
+        return self.relativeAddresses;
+    }
+
+    pub fn getGo(&self) -> bool {
+        return self.go;
+    }
+
+    pub fn getRust(&self) -> bool {
+        return self.rust;
+    }
+
+    pub fn getDotnet(&self) -> bool {
+        return self.dotnet;
+    }
+
+    pub fn getJava(&self) -> bool {
+        return self.java;
+    }
+
+    pub fn getDex(&self) -> bool {
+        return self.dex;
+    }
+
+    pub fn getDexReferences(&self) -> bool {
+        return self.dexReferences;
+    }
+
+    pub fn getPdf(&self) -> bool {
+        return self.pdf;
+    }
+
+    pub fn getDecompress(&self) -> bool {
+        return self.decompress;
+    }
+
+    pub fn getArchiveDepth(&self) -> usize {
+        return self.archiveDepth;
+    }
+
+    pub fn getMaxExtracted(&self) -> u64 {
+        return self.maxExtracted;
+    }
+
+    pub fn getCarve(&self) -> bool {
+        return self.carve;
+    }
+}
+
+impl Default for ScanOptions {
+    fn default() -> ScanOptions {
+        return ScanOptions::new();
+    }
+}
+
+/// A string found by a `Scanner`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoundString {
+    pub offset: u64,            //bytes past the start of the input
+    pub length: usize,          //in bytes, not including the terminator
+    pub encoding: Encoding,
+    pub text: String,
+    pub section: Option<String>,    //the section of the file the string is in, when the file's format is understood
+    pub address: Option<u64>,       //the virtual address the string is loaded at, when it is known
+    pub member: Option<String>,     //the file inside an archive (e.g. a class in a JAR) the string is in, offset is then from the start of its contents
+    pub stream: Option<u64>,        //the offset of the compressed stream the string was decompressed from, offset is then from the start of the decompressed data
+}
+
+/// Searches byte buffers for strings.
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    options: ScanOptions,
+}
+
+impl Scanner {
+    pub fn new(options: ScanOptions) -> Scanner {
+        return Scanner { options: options };
+    }
+
+    pub fn options(&self) -> &ScanOptions {
+        return &self.options;
+    }
+
+    /// Returns an iterator over the strings in `data`, in the order they appear.
+    pub fn scan<'a>(&'a self, data: &'a [u8]) -> Strings<'a> {
+        return Strings {
+            data: data,
+            options: &self.options,
+            index: 0,
+            end: data.len(),
+        };
+    }
+
+    /// Scans `data` with up to `threads` threads and returns the strings in the order they appear. The result is the
+    /// same as `scan` would give: each thread scans its own chunk, and where a string runs over the end of a chunk the
+    /// start of the next chunk is re-scanned until the two agree again.
+    pub fn scanParallel(&self, data: &[u8], threads: usize) -> Vec<FoundString> {
+        let mut strings: Vec<FoundString> = Vec::new();
+        self.scanParallelWith(data, threads, |found| strings.push(found));
+        return strings;
+    }
+
+    /// Like `scanParallel`, but hands each string to `callback` in file order as soon as it is known, that is as soon
+    /// as the thread searching its chunk (and every chunk before it) is done.
+    pub fn scanParallelWith<F: FnMut(FoundString)>(&self, data: &[u8], threads: usize, mut callback: F) {
+        let chunks = splitChunks(data.len(), threads);
+        thread::scope(|scope| {
+            let mut receivers = vec![];
+            for &(start, end) in chunks.iter() {
+                let (sender, receiver) = mpsc::channel();
+                scope.spawn(move || {
+                    let _ = sender.send(self.scanRange(data, start, end).collect::<Vec<FoundString>>());
+                });
+                receivers.push(receiver);
+            }
+            let mut claimed = 0; //everything before this is part of a string that has already been found
+            for (&(start, _), receiver) in chunks.iter().zip(receivers.into_iter()) { //wait for the chunks in order so the output is in file order
+                let chunkStrings = receiver.recv().expect("a scanning thread panicked");
+                for found in self.stitch(data, start, claimed, chunkStrings) {
+                    claimed = claimEnd(&found);
+                    callback(found);
+                }
+            }
+        });
+    }
+
+    fn scanRange<'a>(&'a self, data: &'a [u8], start: usize, end: usize) -> Strings<'a> { //only looks for strings starting in start..end, they may end past it
+        return Strings {
+            data: data,
+            options: &self.options,
+            index: start,
+            end: end,
+        };
+    }
+
+    fn stitch(&self, data: &[u8], start: usize, claimed: usize, chunkStrings: Vec<FoundString>) -> Vec<FoundString> { //fixes up the strings found in the chunk starting at start given that the previous chunks claimed everything up to claimed
+        if claimed <= start { //nothing ran into this chunk so the thread started in the same state a single threaded scan would have
+            return chunkStrings;
+        }
+        let mut strings: Vec<FoundString> = Vec::new();
+        let mut chunkStrings = chunkStrings.into_iter().peekable();
+        let mut position = claimed;         //a single threaded scan would pick up again here
+        let mut chunkClaimed = start;       //how far the strings the thread found before position reach
+        loop {
+            while chunkStrings.peek().is_some_and(|found| (found.offset as usize) < position) { //the thread found these while a single threaded scan would still have been inside an earlier string
+                chunkClaimed = cmp::max(chunkClaimed, claimEnd(&chunkStrings.next().unwrap()));
+            }
+            if chunkClaimed <= position { //both scans are outside of any string at position, so from here on they agree
+                break;
+            }
+            let mut next = chunkClaimed; //re-scan the part the thread skipped over but a single threaded scan wouldn't
+            for found in self.scanRange(data, position, chunkClaimed) {
+                next = cmp::max(next, claimEnd(&found));
+                strings.push(found);
+            }
+            position = next;
+        }
+        strings.extend(chunkStrings);
+        return strings;
+    }
+
+    /// Returns an iterator over the strings read from `reader`. The input is read `bufferSize` bytes at a time so
+    /// arbitrarily large inputs can be scanned; strings that cross the end of a buffer are carried over to the next one,
+    /// and offsets are counted from the start of the stream.
+    pub fn scanReader<R: Read>(&self, reader: R) -> ReaderStrings<R> {
+        return ReaderStrings {
+            reader: reader,
+            options: self.options.clone(),
+            buffer: Vec::new(),
+            bufferOffset: 0,
+            index: 0,
+            eof: false,
+        };
+    }
+}
+
+/// The iterator returned by `Scanner::scan`.
+pub struct Strings<'a> {
+    data: &'a [u8],
+    options: &'a ScanOptions,
+    index: usize,   //the next index to check for a string
+    end: usize,     //strings starting at or after this aren't looked for
+}
+
+impl<'a> Iterator for Strings<'a> {
+    type Item = FoundString;
+
+    fn next(&mut self) -> Option<FoundString> {
+        while self.index < self.end {
+            let index = self.index;
+            match findStringAt(self.data, index, 0, self.options).0 {
+                Some(found) => {
+                    self.index = claimEnd(&found); //skip the string and its terminator so we don't then find a 3 character string followed by a 2 character one and so on
+                    return Some(found);
+                },
+                None => self.index += 1,
+            }
+        }
+        return None;
+    }
+}
+
+/// The iterator returned by `Scanner::scanReader`.
+pub struct ReaderStrings<R> {
+    reader: R,
+    options: ScanOptions,
+    buffer: Vec<u8>,        //the part of the stream that is still needed
+    bufferOffset: u64,      //the offset in the stream of buffer[0]
+    index: usize,           //the next index in buffer to check for a string
+    eof: bool,              //whether the reader has run out of data
+}
+
+impl<R: Read> ReaderStrings<R> {
+    fn refill(&mut self) -> io::Result<()> { //drops the part of the buffer we are done with and reads until the buffer is full or the stream ends
+        self.buffer.drain(..self.index);
+        self.bufferOffset += self.index as u64;
+        self.index = 0;
+        let mut capacity = self.options.bufferSize;
+        while capacity <= self.buffer.len() { //a string longer than the buffer, so the buffer has to grow
+            capacity *= 2;
+        }
+        let mut filled = self.buffer.len();
+        self.buffer.resize(capacity, 0);
+        while filled < capacity {
+            match self.reader.read(&mut self.buffer[filled..]) {
+                Ok(0) => {
+                    self.eof = true;
+                    break;
+                },
+                Ok(n) => filled += n,
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {},
+                Err(err) => {
+                    self.buffer.truncate(filled);
+                    return Err(err);
+                },
+            }
+        }
+        self.buffer.truncate(filled);
+        return Ok(());
+    }
+}
+
+impl<R: Read> Iterator for ReaderStrings<R> {
+    type Item = io::Result<FoundString>;
+
+    fn next(&mut self) -> Option<io::Result<FoundString>> {
+        loop {
+            if self.index >= self.buffer.len() {
+                if self.eof {
+                    return None;
+                }
+                if let Err(err) = self.refill() {
+                    return Some(Err(err));
+                }
+                continue;
+            }
+            let index = self.index;
+            let (found, extent) = findStringAt(&self.buffer, index, self.bufferOffset, &self.options);
+            if !self.eof && index + extent + LOOKAHEAD > self.buffer.len() { //the check ran into the end of the buffer so we can't be sure of the result yet
+                if let Err(err) = self.refill() {
+                    return Some(Err(err));
+                }
+                continue;
+            }
+            match found {
+                Some(found) => {
+                    self.index = index + found.length + 1; //skip the string and its terminator
+                    return Some(Ok(found));
+                },
+                None => self.index += 1,
+            }
+        }
+    }
+}
+
+fn claimEnd(found: &FoundString) -> usize { //the first index that is checked again after finding a string (skipping the string and its terminator)
+    return found.offset as usize + found.length + 1;
+}
+
+fn splitChunks(length: usize, threads: usize) -> Vec<(usize, usize)> { //splits length bytes into at most threads chunks, the extra bytes at the end (from integer division) are stuck on the last chunk
+    let count = cmp::max(1, cmp::min(threads, length / MIN_CHUNK_SIZE));
+    let chunkSize = length / count;
+    let mut chunks: Vec<(usize, usize)> = Vec::new();
+    for i in 0..count {
+        let end = if i == count - 1 { length } else { (i + 1) * chunkSize };
+        chunks.push((i * chunkSize, end));
+    }
+    return chunks;
+}
+
+fn findStringAt(file: &[u8], index: usize, baseOffset: u64, options: &ScanOptions) -> (Option<FoundString>, usize) { //checks every encoding for a string starting at index, the first one that finds a string wins. Also returns how many bytes past index were looked at
+    let mut extent = 0;
+    for encoding in options.encodings.iter() {
+        let temp = match *encoding { //temp is a tuple; temp.0 is whether or not we found one; temp.1 is the length (in bytes) of the string we found
+            Encoding::SevenBit => checkForString(file, index, options.minLength, !options.nullTerminated, options.unicode),
+            _ => checkForEncodedString(file, index, options.minLength, !options.nullTerminated, options.unicode, *encoding),
+        };
+        if temp.1 as usize > extent {
+            extent = temp.1 as usize;
+        }
+        if temp.0 {
+            let endIndex = index as u64 + temp.1;
+            let text = match *encoding {
+                Encoding::SevenBit => getString(file, index as u64, endIndex),
+                _ => getEncodedString(file, index as u64, endIndex, *encoding),
+            };
+            return (Some(FoundString {
+                offset: baseOffset + index as u64,
+                length: temp.1 as usize,
+                encoding: *encoding,
+                text: text,
+                section: None,
+                address: None,
+                member: None,
+                stream: None,
+            }), extent);
+        }
+    }
+    return (None, extent);
+}
+
+pub fn checkForString(file: &[u8], index: usize, numBytes: usize, nullBytes: bool, utf8: bool) -> (bool, u64) { //bool is whether or not we did, u64 is the length of it if we did (or how far we got if we didn't)
+    let mut isFound = false; //by default we never found it
+    let mut size = 0;   //size=0 is the default
+    let mut i = 0;  //used in the loop{} structure as a counter
+    if !utf8 {
+        loop {
+            if index+i >= file.len() { //a string running into the end of the file doesn't count
+                size = i;
+                break;
+            }
+            if isPrintableASCII(file[index+i]){  //if it is printable, then just loop to go to the next one
+                i += 1; //must increment it so we go to the next character in the file
+            }
+            else { //if it isn't printable then check if it is long enough yet
+                size = i;
+                if size >= numBytes {   //if it is long enough, then check if we should check if it is null terminated
+                    if !nullBytes { //if nullBytes == true, then don't check for them
+                        if file[index+i] == 0 { //null terminated
+                            isFound = true;
+                        }
+                    }
+                    else {
+                        isFound = true;
+                    }
+                }
+                break; //once we find a non-printable character we break
+            }
+        }
+    }
+    if utf8 {   //special string searching logic for utf-8 search since it is considerably slower than searching for ASCII strings
+        let mut numberOfCharacters = 0;     //must keep track of the numberOfCharacters alone since num of characters != num of bytes for UTF-8
+        loop {
+            if index+i >= file.len() {
+                size = i;
+                break;
+            }
+            if isPrintableASCII(file[index+i]){  //if it is printable, then just loop to go to the next one
+                i += 1; //must increment it so we go to the next character in the file
+                numberOfCharacters += 1;    //found 1 character
+            }
+            else {
+                let mut utf8CheckerTuple = (false, 0); //tuple of (whetherFoundUTF8, number of bytes of UTF8)
+                if index+i+1 < file.len() && file[index+i+1] > 127 {
+                    utf8CheckerTuple = isUTF8(file, index+i+1);
+                }
+                if utf8CheckerTuple.0 { //if we found a utf8 character
+                    numberOfCharacters += 1;      //we found one character but more than 1 byte
+                    i += utf8CheckerTuple.1 + 1; //add the number of bytes to our indexIterator
+                }
+                else { //if not then check if we have a string
+                    size = i;
+                    if numberOfCharacters >= numBytes {
+                        if !nullBytes { //if nullBytes == true, then don't check for them
+                            if file[index+i] == 0 { //null terminated
+                                isFound = true;
+                            }
+                        }
+                        else {
+                            isFound = true;
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    return (isFound, size as u64)   //return it as a u64 so it is sufficiently large
+}
+
+pub fn checkForEncodedString(file: &[u8], index: usize, numBytes: usize, nullBytes: bool, unicode: bool, encoding: Encoding) -> (bool, u64) { //same as checkForString but for every other encoding; numBytes is the number of characters needed
+    let mut isFound = false;
+    let mut numberOfCharacters = 0;
+    let mut i = 0;
+    loop {
+        let (printable, len) = isPrintableUnit(file, index+i, unicode, encoding);
+        if printable {
+            i += len;
+            numberOfCharacters += 1;
+            continue;
+        }
+        if numberOfCharacters >= numBytes {
+            if !nullBytes { //the terminator is a code unit of all zeros (e.g. 0x0000 for 16 bit strings)
+                if readUnit(file, index+i, encoding) == Some(0) {
+                    isFound = true;
+                }
+            }
+            else if index+i+encoding.width() <= file.len() { //like the 7 bit search a string running into the end of the file doesn't count
+                isFound = true;
+            }
+        }
+        break;
+    }
+    return (isFound, i as u64);
+}
+
+pub fn getString(file: &[u8], startIndex: u64, endIndex: u64) -> String { //given the indexes in the file and the file, return the string
+    let byteArr = &file[startIndex as usize..endIndex as usize];
+    let str = match str::from_utf8(byteArr) {
+                    Ok(n) => n,
+                    Err(_) => "",
+                };
+    return String::from(str);
+}
+
+pub fn getEncodedString(file: &[u8], startIndex: u64, endIndex: u64, encoding: Encoding) -> String { //given the indexes in the file and the file, decode the string between them
+    let width = encoding.width();
+    if encoding == Encoding::EightBit { //Latin-1 maps directly onto the first 256 unicode code points
+        return file[startIndex as usize..endIndex as usize].iter().map(|&byte| byte as char).collect();
+    }
+    let mut units: Vec<u16> = Vec::new(); //UTF-32 is re-encoded as UTF-16 so both can be decoded the same way
+    let mut i = startIndex as usize;
+    while i+width <= endIndex as usize {
+        let unit = readUnit(file, i, encoding).unwrap();
+        if width == 4 {
+            let mut buf = [0u16; 2];
+            units.extend_from_slice(::std::char::from_u32(unit).unwrap_or('\u{FFFD}').encode_utf16(&mut buf));
+        }
+        else {
+            units.push(unit as u16);
+        }
+        i += width;
+    }
+    return String::from_utf16_lossy(&units);
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding::Encoding;
+    use super::{ScanOptions, Scanner, FoundString};
+    use super::splitChunks;
+    use std::io;
+    use std::io::Read;
+    use super::getString;
+    use super::checkForString;
+    use super::checkForEncodedString;
+    use super::getEncodedString;
+
+    #[test]
+    fn testGetString() {
+        let vec = vec![104u8, 105u8];
+        assert_eq!(String::from("hi"), getString(&vec, 0, 2));
+        let vec = vec![62u8, 194u8, 162u8, 62u8];
+        assert_eq!(String::from("¢"), getString(&vec, 1, 3));
+    }
+
+    #[test]
+    fn testCheckForString() {
+        let vec = vec![10u8, 62u8, 63u8, 64u8, 65u8, 66u8, 10u8, 63u8, 64u8, 65u8, 66u8, 67u8, 0u8, 12u8];
+        let numBytes = 4;
+        let mut nullBytes = false;
+        let utf8 = false;
+        assert_eq!((false, 0), checkForString(&vec, 0, numBytes, nullBytes, utf8));
+        assert_eq!((false, 5), checkForString(&vec, 1, numBytes, nullBytes, utf8));
+        assert_eq!((false, 4), checkForString(&vec, 2, numBytes, nullBytes, utf8));
+        assert_eq!((true, 5), checkForString(&vec, 7, numBytes, nullBytes, utf8));
+        nullBytes = true;
+        assert_eq!((true, 5), checkForString(&vec, 1, numBytes, nullBytes, utf8));
+    }
+
+    #[test]
+    fn testCheckForStringUTF16() {
+        let vec = vec![10u8, 0u8, 104u8, 0u8, 105u8, 0u8, 104u8, 0u8, 105u8, 0u8, 0u8, 0u8, 104u8, 0u8, 105u8, 0u8, 10u8, 0u8, 0u8, 0u8];
+        assert_eq!((false, 0), checkForEncodedString(&vec, 0, 4, false, false, Encoding::LittleEndian16));
+        assert_eq!((true, 8), checkForEncodedString(&vec, 2, 4, false, false, Encoding::LittleEndian16));
+        assert_eq!((false, 4), checkForEncodedString(&vec, 12, 2, false, false, Encoding::LittleEndian16));
+        assert_eq!((true, 4), checkForEncodedString(&vec, 12, 2, true, false, Encoding::LittleEndian16));
+        assert_eq!(String::from("hihi"), getEncodedString(&vec, 2, 10, Encoding::LittleEndian16));
+        let bigEndian = vec![0u8, 104u8, 0u8, 105u8, 0u8, 0u8];
+        assert_eq!((true, 4), checkForEncodedString(&bigEndian, 0, 2, false, false, Encoding::BigEndian16));
+        assert_eq!(String::from("hi"), getEncodedString(&bigEndian, 0, 4, Encoding::BigEndian16));
+    }
+
+    #[test]
+    fn testCheckForStringUTF16Unicode() {
+        let vec = vec![162u8, 0u8, 104u8, 0u8, 61u8, 216u8, 0u8, 222u8, 0u8, 0u8]; //"¢h😀"
+        assert_eq!((false, 0), checkForEncodedString(&vec, 0, 3, false, false, Encoding::LittleEndian16));
+        assert_eq!((true, 8), checkForEncodedString(&vec, 0, 3, false, true, Encoding::LittleEndian16));
+        assert_eq!(String::from("¢h😀"), getEncodedString(&vec, 0, 8, Encoding::LittleEndian16));
+    }
+
+    #[test]
+    fn testCheckForStringUTF32() {
+        let vec = vec![104u8, 0u8, 0u8, 0u8, 105u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8];
+        assert_eq!((true, 8), checkForEncodedString(&vec, 0, 2, false, false, Encoding::LittleEndian32));
+        assert_eq!((false, 0), checkForEncodedString(&vec, 0, 2, false, false, Encoding::BigEndian32));
+        assert_eq!(String::from("hi"), getEncodedString(&vec, 0, 8, Encoding::LittleEndian32));
+        let bigEndian = vec![0u8, 1u8, 246u8, 0u8, 0u8, 0u8, 0u8, 104u8, 0u8, 0u8, 0u8, 0u8]; //"😀h"
+        assert_eq!((false, 0), checkForEncodedString(&bigEndian, 0, 2, false, false, Encoding::BigEndian32));
+        assert_eq!((true, 8), checkForEncodedString(&bigEndian, 0, 2, false, true, Encoding::BigEndian32));
+        assert_eq!(String::from("😀h"), getEncodedString(&bigEndian, 0, 8, Encoding::BigEndian32));
+    }
+
+    #[test]
+    fn testCheckForStringLatin1() {
+        let vec = vec![99u8, 97u8, 102u8, 233u8, 0u8]; //"café"
+        assert_eq!((false, 3), checkForString(&vec, 0, 4, false, false));
+        assert_eq!((true, 4), checkForEncodedString(&vec, 0, 4, false, false, Encoding::EightBit));
+        assert_eq!(String::from("café"), getEncodedString(&vec, 0, 4, Encoding::EightBit));
+    }
+
+    #[test]
+    fn testScan() {
+        let vec = b"\x01abcd\0ab\0efghi\0j\0\0k\0\0\0\0".to_vec();
+        let scanner = Scanner::new(ScanOptions::new());
+        let found: Vec<(u64, String)> = scanner.scan(&vec).map(|found| (found.offset, found.text)).collect();
+        assert_eq!(vec![(1, String::from("abcd")), (9, String::from("efghi"))], found);
+    }
+
+    #[test]
+    fn testScanSeveralEncodings() {
+        let vec = b"abcd\0\x02w\0i\0d\0e\0\0\0".to_vec();
+        let scanner = Scanner::new(ScanOptions::new().encodings(vec![Encoding::SevenBit, Encoding::LittleEndian16]));
+        let found: Vec<(u64, usize, Encoding)> = scanner.scan(&vec).map(|found| (found.offset, found.length, found.encoding)).collect();
+        assert_eq!(vec![(0, 4, Encoding::SevenBit), (6, 8, Encoding::LittleEndian16)], found);
+    }
+
+    struct TrickleReader<'a> { //hands out at most 3 bytes per read, like a slow pipe
+        data: &'a [u8],
+    }
+
+    impl<'a> Read for TrickleReader<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = *[3, buf.len(), self.data.len()].iter().min().unwrap();
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            return Ok(n);
+        }
+    }
+
+    #[test]
+    fn testScanReaderMatchesScan() {
+        let vec = b"\x01abcd\0ab\0a much longer string than the buffer\0\xff\x02w\0i\0d\0e\0\0\0end\0tail".to_vec();
+        for &bufferSize in [1, 2, 5, 7, 64].iter() {
+            for &nullTerminated in [true, false].iter() {
+                let scanner = Scanner::new(ScanOptions::new().bufferSize(bufferSize).nullTerminated(nullTerminated).encodings(vec![Encoding::SevenBit, Encoding::LittleEndian16]));
+                let expected: Vec<FoundString> = scanner.scan(&vec).collect();
+                let streamed: Vec<FoundString> = scanner.scanReader(TrickleReader { data: &vec }).map(|found| found.unwrap()).collect();
+                assert_eq!(expected, streamed);
+            }
+        }
+    }
+
+    #[test]
+    fn testScanReaderOffsets() {
+        let vec = b"\x01\x01\x01\x01first\0\x01\x01\x01\x01\x01second\0".to_vec();
+        let scanner = Scanner::new(ScanOptions::new().bufferSize(4));
+        let found: Vec<(u64, String)> = scanner.scanReader(&vec[..]).map(|found| found.unwrap()).map(|found| (found.offset, found.text)).collect();
+        assert_eq!(vec![(4, String::from("first")), (15, String::from("second"))], found);
+    }
+
+    #[test]
+    fn testSplitChunks() {
+        assert_eq!(vec![(0, 100)], splitChunks(100, 4));
+        assert_eq!(vec![(0, 5000), (5000, 10001)], splitChunks(10001, 2));
+        assert_eq!(vec![(0, 4096), (4096, 8192), (8192, 12288)], splitChunks(12288, 16));
+    }
+
+    #[test]
+    fn testScanParallelMatchesScan() {
+        let mut vec: Vec<u8> = Vec::new(); //strings of every length, some of them wide, so plenty cross the chunk boundaries
+        for i in 0..3000 {
+            if i % 7 == 0 {
+                for _ in 0..(i % 40) {
+                    vec.extend_from_slice(b"w\0");
+                }
+                vec.extend_from_slice(b"\0\0");
+            }
+            else {
+                vec.extend((0..(i % 53)).map(|j| b'a' + (j % 26) as u8));
+                vec.push(if i % 3 == 0 { 1 } else { 0 });
+            }
+        }
+        for &nullTerminated in [true, false].iter() {
+            let scanner = Scanner::new(ScanOptions::new().nullTerminated(nullTerminated).encodings(vec![Encoding::LittleEndian16, Encoding::SevenBit, Encoding::BigEndian16]));
+            let expected: Vec<FoundString> = scanner.scan(&vec).collect();
+            for &threads in [1, 2, 3, 8, 64].iter() {
+                assert_eq!(expected, scanner.scanParallel(&vec, threads));
+            }
+        }
+    }
+
+    #[test]
+    fn testScanParallelStringAcrossChunks() {
+        let mut vec = vec![1u8; 8192];
+        for i in 4000..4200 {
+            vec[i] = b'x';
+        }
+        vec[4200] = 0;
+        let scanner = Scanner::new(ScanOptions::new());
+        let found = scanner.scanParallel(&vec, 2);
+        assert_eq!(1, found.len());
+        assert_eq!(4000, found[0].offset);
+        assert_eq!(200, found[0].length);
+    }
+}
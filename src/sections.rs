@@ -0,0 +1,564 @@
+use std::str;
+use std::cmp;
+use std::cell::Cell;
+use std::borrow::Cow;
+
+use elf;
+use pe;
+use macho;
+use golang;
+use rust;
+use java;
+use dex;
+use compression;
+use archive;
+use pdf;
+use wasm;
+use zip;
+use encoding::Encoding;
+use scanner::{Scanner, FoundString};
+
+pub const MAX_POINTED: u64 = 1 << 20; //longer "strings" are taken to be something else that happens to look like a pointer and length
+const ARCHIVE_HEAD: u64 = 1 << 16; //how much compressed data is decompressed to tell whether it is an archive
+
+/// A part of an executable or object file, as found by the parser for its format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub offset: u64,            //where the section starts in the file
+    pub size: u64,              //in bytes
+    pub address: Option<u64>,   //the virtual address the section is loaded at, None if it isn't loaded
+    pub imageBase: u64,         //the address the file is meant to be loaded at, which relative addresses (RVAs) count from
+    pub isData: bool,           //whether the section holds initialized data (rather than code, symbols, debugging info...)
+}
+
+impl Section {
+    /// The bytes of the section, cut short if the file is.
+    pub fn bytes<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+        let start = cmp::min(self.offset, data.len() as u64) as usize;
+        let end = cmp::min(self.offset.saturating_add(self.size), data.len() as u64) as usize;
+        return &data[start..end];
+    }
+
+    pub fn contains(&self, offset: u64) -> bool {
+        return offset >= self.offset && offset - self.offset < self.size;
+    }
+
+    /// Tags a string found in the file with this section and the address it is loaded at.
+    pub fn annotate(&self, mut found: FoundString) -> FoundString {
+        found.address = self.address.and_then(|address| address.checked_add(found.offset - self.offset));
+        found.section = Some(self.name.clone());
+        return found;
+    }
+}
+
+/// Which parts of an executable or object file are searched. Files that aren't understood are always searched from end to end.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SectionFilter {
+    Data,               //only the initialized data sections, like GNU strings used to
+    All,                //the whole file
+    Named(Vec<String>), //only the sections with these names
+}
+
+impl SectionFilter {
+    /// Parses "data", "all" or a comma separated list of section names (e.g. ".rodata,.comment").
+    pub fn fromName(name: &str) -> SectionFilter {
+        return match name {
+            "data" => SectionFilter::Data,
+            "all" => SectionFilter::All,
+            names => SectionFilter::Named(names.split(',').map(|name| String::from(name.trim())).collect()),
+        };
+    }
+
+    pub fn selects(&self, section: &Section) -> bool {
+        return match *self {
+            SectionFilter::Data => section.isData,
+            SectionFilter::All => true,
+            SectionFilter::Named(ref names) => names.contains(&section.name),
+        };
+    }
+}
+
+/// Returns the sections of data if it is in a format that is understood (ELF, PE, Mach-O, including fat files, or
+/// WebAssembly), sorted by where they are in the file. Returns None otherwise.
+pub fn findSections(data: &[u8]) -> Option<Vec<Section>> {
+    let mut sections = match elf::parseSections(data) {
+        Some(sections) => sections,
+        None => match pe::parseSections(data) {
+            Some(sections) => sections,
+            None => match macho::parseSections(data) {
+                Some(sections) => sections,
+                None => wasm::parseSections(data)?,
+            },
+        },
+    };
+    sections.sort_by_key(|section| section.offset);
+    return Some(sections);
+}
+
+/// Searches the sections of data picked by filter, passing the strings found (tagged with their section) to callback in
+/// file order. Files that aren't understood are searched from end to end, and so is the whole file for `SectionFilter::All`,
+/// in which case the strings that start in a section are still tagged with it. With `ScanOptions::resources` the
+/// resources of PE files are decoded rather than searched. With `ScanOptions::go` or `ScanOptions::rust` the strings of Go
+/// or Rust binaries are split at the lengths given by the pointers to them, replacing the (usually run together) strings
+/// found by searching, and the Go build info or Rust panic locations are added. With `ScanOptions::dotnet` the string
+/// heaps of .NET assemblies are decoded, tagged with their heap, and replace what searching finds in them even when the
+/// section they are in (usually .text) isn't searched. In these cases all the strings are collected before any are passed on.
+/// With `ScanOptions::java` and `ScanOptions::dex` Java class files and Android DEX files, on their own or in JARs and
+/// APKs, are decoded instead of searched, and so are PDFs with `ScanOptions::pdf`. With `ScanOptions::decompress` (the
+/// default) compressed data is decompressed and what it decompresses to is scanned instead (a buffer at a time, unless
+/// it is an archive), up to `ScanOptions::maxExtracted` after which the rest is scanned as it is, and with
+/// `ScanOptions::archiveDepth` (4 by default) the files in archives are scanned as if they were separate files. With
+/// `ScanOptions::carve` the compressed streams in other files are found wherever they start and what they decompress to
+/// is scanned as well. The names in WebAssembly modules (of imports, exports and custom sections, and those in the `name`
+/// section) are always decoded and tagged with where they came from.
+pub fn scanSections<F: FnMut(FoundString)>(scanner: &Scanner, data: &[u8], filter: &SectionFilter, threads: usize, mut callback: F) {
+    let budget = Cell::new(scanner.options().getMaxExtracted()); //shared by everything in the file
+    scanFile(scanner, data, filter, threads, 0, true, &budget, &mut callback);
+}
+
+fn scanFile(scanner: &Scanner, data: &[u8], filter: &SectionFilter, threads: usize, depth: usize, decompress: bool, budget: &Cell<u64>, callback: &mut dyn FnMut(FoundString)) { //scanSections for a file, or a file in depth levels of archives
+    let options = scanner.options();
+    if decompress && options.getDecompress() {
+        if let Some((_, head, _)) = compression::decompressUpTo(data, cmp::min(budget.get(), ARCHIVE_HEAD)) {
+            if !(depth < options.getArchiveDepth() && archive::archiveMembers(&head).is_some()) { //only archives need all of it at once
+                let decompressed = compression::scanDecompressed(scanner, data, budget.get(), &mut *callback).unwrap_or(0); //reading from memory can't fail
+                budget.set(budget.get() - decompressed);
+                return;
+            }
+        }
+        if let Some((_, decompressed, read)) = compression::decompressUpTo(data, budget.get()) {
+            budget.set(budget.get() - decompressed.len() as u64);
+            scanFile(scanner, &decompressed, filter, threads, depth, false, budget, &mut |found| { //a stream in the stream is left as it is, so offsets are never into more than one
+                callback(if found.member.is_none() { FoundString { stream: Some(0), ..found } } else { found }); //the offsets of the files in a compressed archive are into the file
+            });
+            for found in scanner.scan(&data[read..]) { //past the limit what's left is searched as it is
+                callback(FoundString { offset: found.offset + read as u64, ..found });
+            }
+            return;
+        }
+    }
+    let decode = |data: &[u8]| { //the formats that are decoded whole
+        let decoded = if options.getJava() { java::classStrings(data, options.getMinLength()) } else { None };
+        let decoded = if decoded.is_none() && options.getDex() { dex::dexStrings(data, options.getMinLength(), options.getDexReferences()) } else { decoded };
+        return if decoded.is_none() && options.getPdf() { pdf::pdfStrings(data, scanner, budget) } else { decoded };
+    };
+    let decoding = options.getJava() || options.getDex() || options.getPdf();
+    let decoded = if decoding { decode(data) } else { None };
+    let decoded = if decoded.is_none() && decoding && depth >= options.getArchiveDepth() { zip::decodeMembers(data, scanner, budget, &decode) } else { decoded }; //JARs and APKs are still decoded when their files aren't scanned like other archives
+    if let Some(strings) = decoded {
+        for found in strings {
+            callback(found);
+        }
+        return;
+    }
+    if depth < options.getArchiveDepth() {
+        if let Some(members) = archive::archiveMembers(data) {
+            for member in members {
+                let contents = match member.contents(data, budget.get()) {
+                    Some(contents) => contents,
+                    None => continue,
+                };
+                if let Cow::Owned(ref extracted) = contents {
+                    budget.set(budget.get() - extracted.len() as u64);
+                }
+                scanFile(scanner, &contents, filter, threads, depth + 1, true, budget, &mut |found| {
+                    let name = match found.member {
+                        Some(ref inner) => format!("{}!{}", member.name, inner),
+                        None => member.name.clone(),
+                    };
+                    callback(FoundString { member: Some(name), ..found });
+                });
+            }
+            return;
+        }
+    }
+    if !(decompress && options.getCarve()) { //what is decompressed isn't carved, so offsets are never into more than one stream
+        return scanContents(scanner, data, filter, threads, callback);
+    }
+    let mut strings: Vec<(u64, FoundString)> = Vec::new(); //with the offset in the file they are sorted by, that of the stream for those in one
+    scanContents(scanner, data, filter, threads, &mut |found| strings.push((found.offset, found)));
+    for offset in 0..data.len() {
+        if budget.get() == 0 {
+            break;
+        }
+        if compression::Compression::detect(&data[offset..]).is_none() { //quick to rule out, unlike trying to decompress
+            continue;
+        }
+        if let Some((_, decompressed)) = compression::decompressStream(&data[offset..], budget.get()) {
+            budget.set(budget.get() - decompressed.len() as u64);
+            scanFile(scanner, &decompressed, filter, threads, depth, false, budget, &mut |found| {
+                strings.push((offset as u64, if found.member.is_none() { FoundString { stream: Some(offset as u64), ..found } } else { found }));
+            });
+        }
+    }
+    strings.sort_by_key(|&(offset, _)| offset);
+    for (_, found) in strings {
+        callback(found);
+    }
+}
+
+fn scanContents(scanner: &Scanner, data: &[u8], filter: &SectionFilter, threads: usize, callback: &mut dyn FnMut(FoundString)) { //scanFile for a file that isn't compressed, decoded whole or an archive
+    let options = scanner.options();
+    let sections = match findSections(data) {
+        Some(sections) => sections,
+        None => return searchSections(scanner, data, filter, threads, callback),
+    };
+    let mut decoded: Option<Vec<FoundString>> = None; //the strings found by understanding the file rather than searching it
+    let mut covered: Vec<(u64, u64)> = Vec::new(); //the parts of the file they cover, where searching finds nothing new
+    let split = if options.getGo() { golang::goStrings(data, &sections, options) } else { None };
+    let split = if split.is_none() && options.getRust() { rust::rustStrings(data, &sections, options) } else { split };
+    if let Some(strings) = split {
+        covered.extend(strings.iter().map(|found| (found.offset, found.offset + found.length as u64)));
+        decoded = Some(strings);
+    }
+    if let Some(heaps) = if options.getDotnet() { pe::metadataStrings(data, options.getMinLength()) } else { None } {
+        for (span, strings) in heaps {
+            covered.push(span);
+            decoded.get_or_insert_with(Vec::new).extend(strings);
+        }
+    }
+    if let Some(strings) = wasm::nameStrings(data, options.getMinLength()) {
+        covered.extend(strings.iter().map(|found| (found.offset, found.offset + found.length as u64)));
+        decoded.get_or_insert_with(Vec::new).extend(strings);
+    }
+    let decoded = match decoded {
+        Some(decoded) => decoded,
+        None => return searchSections(scanner, data, filter, threads, callback),
+    };
+    covered.sort();
+    let mut ranges: Vec<(u64, u64)> = Vec::new(); //merged so they don't overlap
+    for (start, end) in covered {
+        match ranges.last_mut() {
+            Some(last) if start < last.1 => last.1 = if end > last.1 { end } else { last.1 },
+            _ => ranges.push((start, end)),
+        }
+    }
+    let mut strings: Vec<FoundString> = Vec::new();
+    searchSections(scanner, data, filter, threads, |found| {
+        let index = ranges.partition_point(|range| range.1 <= found.offset); //the first that ends after found starts
+        if index == ranges.len() || ranges[index].0 >= found.offset + found.length as u64 { //doesn't overlap them
+            strings.push(found);
+        }
+    });
+    for found in decoded {
+        let section = sections.iter().find(|section| section.contains(found.offset));
+        let selected = match (found.section.as_ref(), filter) {
+            (Some(tag), &SectionFilter::Named(ref names)) => names.contains(tag), //a heap, picked by its name
+            (Some(_), _) => true,
+            (None, &SectionFilter::All) => true,
+            (None, _) => section.is_some_and(|section| filter.selects(section)),
+        };
+        if selected {
+            strings.push(annotated(found, section, options.getRelativeAddresses()));
+        }
+    }
+    strings.sort_by_key(|found| found.offset);
+    for found in strings {
+        callback(found);
+    }
+}
+
+fn searchSections<F: FnMut(FoundString)>(scanner: &Scanner, data: &[u8], filter: &SectionFilter, threads: usize, mut callback: F) { //scanSections without the Go, Rust, .NET or WebAssembly strings
+    let sections = match findSections(data) {
+        Some(sections) => sections,
+        None => return scanRange(scanner, data, 0, data.len() as u64, threads, &mut callback),
+    };
+    let options = scanner.options();
+    let relative = options.getRelativeAddresses();
+    let resources = if options.getResources() { pe::resourceStrings(data, options.getMinLength()) } else { None };
+    let decoded = resources.as_ref().and_then(|&(root, _)| sections.iter().position(|section| section.contains(root))); //the section whose strings are decoded instead of searched
+    if *filter == SectionFilter::All {
+        let mut lookup = |found: FoundString| {
+            let section = sections.iter().find(|section| section.contains(found.offset));
+            callback(annotated(found, section, relative));
+        };
+        let decodedRange = decoded.map(|index| (sections[index].offset, sections[index].offset + sections[index].size));
+        match (decodedRange, resources) {
+            (Some((start, end)), Some((_, strings))) => {
+                scanRange(scanner, data, 0, start, threads, &mut lookup);
+                for found in strings {
+                    lookup(found);
+                }
+                scanRange(scanner, data, end, data.len() as u64, threads, &mut lookup);
+            },
+            _ => scanRange(scanner, data, 0, data.len() as u64, threads, &mut lookup),
+        }
+        return;
+    }
+    let mut strings = resources.map(|(_, strings)| strings);
+    let mut end: u64 = 0; //where the last searched section ended, so overlapping sections aren't searched twice
+    for (index, section) in sections.iter().enumerate() {
+        if !filter.selects(section) || section.size == 0 || section.offset < end {
+            continue;
+        }
+        end = section.offset.saturating_add(section.size);
+        if decoded == Some(index) {
+            for found in strings.take().unwrap_or_default() {
+                callback(annotated(found, Some(section), relative));
+            }
+            continue;
+        }
+        scanRange(scanner, data, section.offset, end, threads, &mut |found: FoundString| callback(annotated(found, Some(section), relative)));
+    }
+}
+
+fn annotated(found: FoundString, section: Option<&Section>, relative: bool) -> FoundString { //tags found with the section it is in, unless it was decoded from the section and so already is
+    let section = match section {
+        Some(section) => section,
+        None => return found,
+    };
+    let mut found = if found.section.is_none() { section.annotate(found) } else { found };
+    if relative {
+        found.address = found.address.map(|address| address - section.imageBase);
+    }
+    return found;
+}
+
+fn scanRange<F: FnMut(FoundString)>(scanner: &Scanner, data: &[u8], start: u64, end: u64, threads: usize, callback: &mut F) { //searches data[start..end], giving offsets from the start of data
+    let start = if start > data.len() as u64 { data.len() } else { start as usize };
+    let end = if end > data.len() as u64 { data.len() } else { end as usize };
+    if start >= end {
+        return;
+    }
+    if threads > 1 {
+        scanner.scanParallelWith(&data[start..end], threads, |mut found| {
+            found.offset += start as u64;
+            callback(found);
+        });
+        return;
+    }
+    for mut found in scanner.scan(&data[start..end]) {
+        found.offset += start as u64;
+        callback(found);
+    }
+}
+
+/// The file offset the data loaded at a virtual address comes from, None if no section is loaded there.
+pub fn addressToOffset(sections: &[Section], address: u64) -> Option<u64> {
+    let section = sections.iter().find(|section| match section.address {
+        Some(start) => address >= start && address - start < section.size,
+        None => false,
+    })?;
+    return section.offset.checked_add(address - section.address?);
+}
+
+/// Reads the length bytes at offset as a string, None if they aren't printable UTF-8 (tabs and newlines are allowed).
+pub fn readUtf8(data: &[u8], offset: u64, length: u64) -> Option<FoundString> {
+    if length == 0 || length > MAX_POINTED || offset.checked_add(length).is_none_or(|end| end > data.len() as u64) {
+        return None;
+    }
+    let text = str::from_utf8(&data[offset as usize..(offset + length) as usize]).ok()?;
+    if text.chars().any(|char| char.is_control() && char != '\t' && char != '\n') {
+        return None;
+    }
+    return Some(FoundString {
+        offset: offset,
+        length: length as usize,
+        encoding: Encoding::SevenBit,
+        text: String::from(text),
+        section: None,
+        address: None,
+        member: None,
+        stream: None,
+    });
+}
+
+/// Finds the strings pointed at by the pointer and length pairs (Go's string headers, Rust's `&str`) aligned to ptrSize
+/// in the loaded data sections. Languages that don't terminate their strings use these to know where they end.
+pub fn pointedStrings(data: &[u8], sections: &[Section], ptrSize: usize, bigEndian: bool) -> Vec<FoundString> {
+    let mut strings: Vec<FoundString> = Vec::new();
+    let size = ptrSize as u64;
+    for section in sections.iter().filter(|section| section.isData && section.address.is_some()) {
+        let end = cmp::min(section.offset.saturating_add(section.size), data.len() as u64);
+        let mut pair = section.offset.saturating_add((size - section.address.unwrap() % size) % size); //pairs are aligned in memory
+        while pair < end && end - pair >= 2 * size {
+            let address = readUInt(data, pair, ptrSize, bigEndian);
+            let length = readUInt(data, pair + size, ptrSize, bigEndian);
+            if let (Some(address), Some(length)) = (address, length) {
+                if let Some(found) = addressToOffset(sections, address).and_then(|offset| readUtf8(data, offset, length)) {
+                    strings.push(found);
+                    pair += 2 * size;
+                    continue;
+                }
+            }
+            pair += size;
+        }
+    }
+    return strings;
+}
+
+/// The size of a pointer and whether integers are big endian in an ELF, PE or (thin) Mach-O file, None for anything else.
+pub fn pointerLayout(data: &[u8]) -> Option<(usize, bool)> {
+    if elf::isElf(data) {
+        let ptrSize = match data[4] { 1 => 4, 2 => 8, _ => return None };
+        return Some((ptrSize, data[5] == 2));
+    }
+    if let Some(ptrSize) = pe::pointerSize(data) {
+        return Some((ptrSize, false));
+    }
+    return match readUInt(data, 0, 4, false)? {
+        0xfeedface => Some((4, false)),
+        0xfeedfacf => Some((8, false)),
+        0xcefaedfe => Some((4, true)),
+        0xcffaedfe => Some((8, true)),
+        _ => None,
+    };
+}
+
+/// Reads a size byte (at most 8) unsigned integer at offset, None if it would read past the end of data.
+pub fn readUInt(data: &[u8], offset: u64, size: usize, bigEndian: bool) -> Option<u64> {
+    if offset.checked_add(size as u64).is_none_or(|end| end > data.len() as u64) {
+        return None;
+    }
+    let bytes = &data[offset as usize..offset as usize + size];
+    let mut value: u64 = 0;
+    for i in 0..size {
+        let byte = if bigEndian { bytes[i] } else { bytes[size-1-i] };
+        value = value << 8 | byte as u64;
+    }
+    return Some(value);
+}
+
+/// Reads an unsigned LEB128 number (Go's uvarint) at offset, moving offset past it. None if it runs past the end of data.
+pub fn readUleb128(data: &[u8], offset: &mut u64) -> Option<u64> {
+    let mut value: u64 = 0;
+    for shift in 0..10 {
+        let byte = readUInt(data, *offset, 1, false)?;
+        *offset += 1;
+        value |= (byte & 0x7f) << (7 * shift);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    return None;
+}
+
+/// Reads the null terminated string at offset, None if it isn't terminated before the end of data.
+pub fn readCString(data: &[u8], offset: u64) -> Option<String> {
+    if offset >= data.len() as u64 {
+        return None;
+    }
+    let bytes = &data[offset as usize..];
+    let end = bytes.iter().position(|byte| *byte == 0)?;
+    return Some(String::from_utf8_lossy(&bytes[..end]).into_owned());
+}
+
+#[cfg(test)]
+mod tests {
+    use scanner::{ScanOptions, Scanner, FoundString};
+    use testing::readFile;
+    use super::{SectionFilter, scanSections, readUInt, readCString, readUleb128};
+
+    fn scanWith(options: ScanOptions, data: &[u8], filter: &str, threads: usize) -> Vec<FoundString> {
+        let mut strings: Vec<FoundString> = Vec::new();
+        scanSections(&Scanner::new(options), data, &SectionFilter::fromName(filter), threads, |found| strings.push(found));
+        return strings;
+    }
+
+    fn scan(data: &[u8], filter: &str, threads: usize) -> Vec<FoundString> {
+        return scanWith(ScanOptions::new(), data, filter, threads);
+    }
+
+    fn describe(strings: &[FoundString]) -> Vec<String> { //section:address:string
+        return strings.iter().map(|found| format!("{}:{:x}:{}", found.section.clone().unwrap_or_default(), found.address.unwrap_or(0), found.text)).collect();
+    }
+
+    #[test]
+    fn testReadUInt() {
+        let data = vec![1u8, 2u8, 3u8, 4u8];
+        assert_eq!(Some(0x0201), readUInt(&data, 0, 2, false));
+        assert_eq!(Some(0x020304), readUInt(&data, 1, 3, true));
+        assert_eq!(None, readUInt(&data, 2, 4, true));
+        assert_eq!(None, readUInt(&data, u64::MAX, 2, false));
+        assert_eq!(Some(String::from("ab")), readCString(b"xab\0", 1));
+        assert_eq!(None, readCString(b"xab", 1));
+        let mut offset = 1;
+        assert_eq!(Some(624485), readUleb128(b"\0\xe5\x8e\x26", &mut offset));
+        assert_eq!(4, offset);
+        assert_eq!(None, readUleb128(b"\x80", &mut 0));
+    }
+
+    #[test]
+    fn testScanSections() {
+        let data = readFile("./testCases/a.out");
+        let strings = scan(&data, "data", 1);
+        let interp = &strings[0];
+        assert_eq!((568, Some(String::from(".interp")), Some(0x400238)), (interp.offset, interp.section.clone(), interp.address));
+        assert!(strings.iter().all(|found| found.section.as_ref().is_some_and(|section| section != ".text" && section != ".strtab")));
+        let comment = strings.iter().find(|found| found.section == Some(String::from(".comment"))).unwrap();
+        assert_eq!(None, comment.address); //not loaded
+        assert_eq!(strings, scan(&data, "data", 4));
+
+        let all = scan(&data, "all", 1);
+        assert!(all.len() > strings.len());
+        assert!(all.iter().any(|found| found.section == Some(String::from(".strtab"))));
+        assert!(strings.iter().all(|found| all.contains(found)));
+        let named: Vec<String> = scan(&data, ".rodata,.comment", 1).iter().map(|found| format!("{}:{}", found.section.as_ref().unwrap(), found.text)).collect();
+        assert_eq!(vec![".rodata:Hello World!", ".comment:GCC: (Ubuntu 4.8.4-2ubuntu1~14.04) 4.8.4", ".comment:GCC: (Ubuntu 4.8.2-19ubuntu1) 4.8.2"], named);
+
+        let mut patched = data.clone(); //a .rodata that starts just short of the end of the address space is dropped
+        let shoff = readUInt(&data, 0x28, 8, false).unwrap();
+        let rodata = (0..readUInt(&data, 0x3C, 2, false).unwrap()).map(|index| shoff + index * 64).find(|header| readUInt(&data, header + 0x18, 8, false) == Some(2400)).unwrap() as usize;
+        patched[rodata + 0x18..rodata + 0x20].copy_from_slice(b"\xf0\xff\xff\xff\xff\xff\xff\xff");
+        let kept: Vec<FoundString> = strings.iter().filter(|found| found.section != Some(String::from(".rodata"))).cloned().collect();
+        assert_eq!(kept, scan(&patched, "data", 1));
+
+        let notElf = b"\x7fELG\0hello world\0".to_vec(); //files that aren't understood are searched from end to end
+        assert_eq!(vec![(5, None)], scan(&notElf, "data", 1).iter().map(|found| (found.offset, found.section.clone())).collect::<Vec<(u64, Option<String>)>>());
+    }
+
+    #[test]
+    fn testScanPe() {
+        let data = readFile("./testCases/hello.exe");
+        assert_eq!(vec![".rdata:140002010:Hello from rdata"], describe(&scan(&data, "data", 1)));
+        let options = ScanOptions::new().resources(true).relativeAddresses(true);
+        let strings = scanWith(options.clone(), &data, "data", 1);
+        assert_eq!(vec![".rdata:2010:Hello from rdata", ".rsrc:string:1:30a4:Resource string one", ".rsrc:string:2:30cc:Second",
+            ".rsrc:version:CompanyName:31ac:Example Corp", ".rsrc:version:FileDescription:31f0:RStrings test binary", ".rsrc:version:ProductVersion:3240:1.2.3"], describe(&strings));
+        assert_eq!(strings, scanWith(options.clone(), &data, "data", 4));
+        let all = scanWith(options, &data, "all", 1);
+        assert_eq!(vec![":0:.text", ":0:`.rdata", ":0:@.rsrc", ".text:100d:code bytes here"], &describe(&all)[..4]); //the section table is outside of any section
+        assert_eq!(&strings[..], &all[4..]);
+    }
+
+    #[test]
+    fn testScanMachO() {
+        let data = readFile("./testCases/hello.fat");
+        let strings = scan(&data, "data", 1);
+        assert_eq!(vec!["x86_64:__TEXT,__cstring:a:Hello from x86_64", "x86_64:__TEXT,__cstring:1c:Second cstring", "x86_64:__TEXT,__objc_methname:2b:initWithFrame:",
+            "x86_64:__DATA,__data:5a:data section text", "arm64:__TEXT,__cstring:8:Hello from arm64", "arm64:__TEXT,__objc_methname:19:viewDidLoad"], describe(&strings));
+        assert_eq!(48 + 642, strings[0].offset);
+        assert_eq!(strings, scan(&data, "data", 3));
+    }
+
+    #[test]
+    fn testScanArchives() {
+        let data = readFile("./testCases/bundle.tar");
+        let files = |strings: Vec<FoundString>| -> Vec<(Option<String>, String)> { strings.into_iter().map(|found| (found.member, found.text)).collect() };
+        let found = files(scan(&data, "data", 1));
+        assert!(found.contains(&(Some(String::from("bundle/notes.txt")), String::from("Remember the milk"))));
+        assert!(found.contains(&(Some(String::from("bundle/inner.zip!deep/secret.txt")), String::from("The inner secret is swordfish"))));
+        let found = files(scanWith(ScanOptions::new().maxExtracted(16), &data, "data", 1)); //too little to decompress the zip archive's file
+        assert!(found.contains(&(Some(String::from("bundle/notes.txt")), String::from("Remember the milk"))));
+        assert!(!found.iter().any(|&(_, ref text)| text.contains("swordfish")));
+        assert!(scanWith(ScanOptions::new().archiveDepth(0), &data, "data", 1).iter().all(|found| found.member.is_none()));
+        let strings = scan(&readFile("./testCases/hello.deb"), "data", 1);
+        let message = strings.iter().find(|found| found.text == "Hello from the deb package").unwrap();
+        assert_eq!((Some(String::from("data.tar.xz!./usr/share/doc/hello/message.txt")), 0, None), (message.member.clone(), message.offset, message.stream));
+    }
+
+    #[test]
+    fn testCarve() {
+        let data = readFile("./testCases/firmware.bin");
+        let locations = |strings: Vec<FoundString>| -> Vec<(Option<u64>, u64, String)> { strings.into_iter().map(|found| (found.stream, found.offset, found.text)).collect() };
+        assert_eq!(vec![(None, 0x185, String::from("plain firmware string"))], locations(scan(&data, "data", 1)));
+        let found = locations(scanWith(ScanOptions::new().carve(true), &data, "data", 1));
+        assert_eq!(vec![(Some(0x65), 0, String::from("kernel cmdline: console=ttyS0 root=/dev/mtdblock2")), (Some(0x65), 50, String::from("kernel cmdline: console=ttyS0 root=/dev/mtdblock2")), (Some(0x65), 100, String::from("kernel cmdline: console=ttyS0 root=/dev/mtdblock2")),
+                (Some(0xc7), 0, String::from("The root password is changeme")), (Some(0xc7), 30, String::from("The root password is changeme")), (Some(0xc7), 60, String::from("The root password is changeme")),
+                (Some(0x121), 0, String::from("lzma squashed config: telnetd enabled")), (Some(0x121), 38, String::from("lzma squashed config: telnetd enabled")), (Some(0x121), 76, String::from("lzma squashed config: telnetd enabled")),
+                (None, 0x185, String::from("plain firmware string"))], found);
+        let found = locations(scanWith(ScanOptions::new().carve(true).maxExtracted(200), &data, "data", 1)); //enough for the first stream and part of the second
+        assert!(found.contains(&(Some(0xc7), 0, String::from("The root password is changeme"))) && !found.iter().any(|&(_, _, ref text)| text.contains("lzma")));
+    }
+}
@@ -0,0 +1,204 @@
+use std::io;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use glob;
+use glob::Pattern;
+use walkdir::WalkDir;
+
+/// Turns the paths given on the command line into the list of files to search: glob patterns are expanded and, when
+/// searching recursively, directories are walked.
+///
+/// ```
+/// use rstrings::FileWalker;
+/// let walker = FileWalker::new().recursive(true).include("*.so").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileWalker {
+    recursive: bool,            //whether directories are searched, otherwise they are an error
+    followSymlinks: bool,       //whether symlinks found while walking a directory are followed (ones given directly always are)
+    include: Vec<Pattern>,      //if not empty only files matching one of these are searched
+    exclude: Vec<Pattern>,      //files and directories matching one of these are skipped
+}
+
+impl FileWalker {
+    pub fn new() -> FileWalker {
+        return FileWalker {
+            recursive: false,
+            followSymlinks: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        };
+    }
+
+    pub fn recursive(mut self, recursive: bool) -> FileWalker {
+        self.recursive = recursive;
+        return self;
+    }
+
+    pub fn followSymlinks(mut self, followSymlinks: bool) -> FileWalker {
+        self.followSymlinks = followSymlinks;
+        return self;
+    }
+
+    /// Only search files matching `pattern`. Patterns without a `/` are matched against the file name, others against the whole path.
+    pub fn include(mut self, pattern: &str) -> Result<FileWalker, String> {
+        self.include.push(parsePattern(pattern)?);
+        return Ok(self);
+    }
+
+    /// Skip files and directories matching `pattern`, matched the same way as `include`.
+    pub fn exclude(mut self, pattern: &str) -> Result<FileWalker, String> {
+        self.exclude.push(parsePattern(pattern)?);
+        return Ok(self);
+    }
+
+    /// Returns the files to search in the order they should be searched (the order of `paths`, each directory sorted by
+    /// name). Paths that can't be read are returned as errors so the caller can report them and carry on.
+    pub fn walk(&self, paths: &[String]) -> Vec<io::Result<PathBuf>> {
+        let mut files: Vec<io::Result<PathBuf>> = Vec::new();
+        for path in paths.iter() {
+            if !Path::new(path).exists() && isGlob(path) { //let the shell-less (or quoted) globs through, like "firmware/*.bin"
+                match glob::glob(path) {
+                    Ok(matches) => {
+                        for found in matches {
+                            match found {
+                                Ok(found) => self.walkPath(&found, &mut files),
+                                Err(err) => files.push(Err(err.into())),
+                            }
+                        }
+                    },
+                    Err(err) => files.push(Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{}: {}", path, err)))),
+                }
+                continue;
+            }
+            self.walkPath(Path::new(path), &mut files);
+        }
+        return files;
+    }
+
+    fn walkPath(&self, path: &Path, files: &mut Vec<io::Result<PathBuf>>) {
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(err) => {
+                files.push(Err(io::Error::new(err.kind(), format!("{}: {}", path.display(), err))));
+                return;
+            },
+        };
+        if !metadata.is_dir() {
+            if self.isIncluded(path) {
+                files.push(Ok(path.to_path_buf()));
+            }
+            return;
+        }
+        if !self.recursive {
+            files.push(Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{}: Is a directory, use --recursive to search it", path.display()))));
+            return;
+        }
+        let walker = WalkDir::new(path).follow_links(self.followSymlinks).sort_by(|a, b| a.file_name().cmp(b.file_name()));
+        let mut entries = walker.into_iter();
+        loop {
+            let entry = match entries.next() {
+                Some(Ok(entry)) => entry,
+                Some(Err(err)) => {
+                    files.push(Err(err.into()));
+                    continue;
+                },
+                None => break,
+            };
+            if entry.depth() > 0 && self.isExcluded(entry.path()) {
+                if entry.file_type().is_dir() {
+                    entries.skip_current_dir();
+                }
+                continue;
+            }
+            if entry.file_type().is_file() && self.isIncluded(entry.path()) {
+                files.push(Ok(entry.path().to_path_buf()));
+            }
+        }
+    }
+
+    fn isIncluded(&self, path: &Path) -> bool {
+        if self.isExcluded(path) {
+            return false;
+        }
+        return self.include.len() == 0 || self.include.iter().any(|pattern| matches(pattern, path));
+    }
+
+    fn isExcluded(&self, path: &Path) -> bool {
+        return self.exclude.iter().any(|pattern| matches(pattern, path));
+    }
+}
+
+impl Default for FileWalker {
+    fn default() -> FileWalker {
+        return FileWalker::new();
+    }
+}
+
+fn parsePattern(pattern: &str) -> Result<Pattern, String> {
+    return Pattern::new(pattern).map_err(|err| format!("Invalid pattern {}: {}", pattern, err));
+}
+
+fn isGlob(path: &str) -> bool {
+    return path.contains('*') || path.contains('?') || path.contains('[');
+}
+
+fn matches(pattern: &Pattern, path: &Path) -> bool { //patterns with a / in them are matched against the whole path, others just against the file name
+    if pattern.as_str().contains('/') {
+        return pattern.matches_path(path);
+    }
+    return match path.file_name() {
+        Some(name) => pattern.matches(&name.to_string_lossy()),
+        None => false,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use super::FileWalker;
+
+    fn makeTree(name: &str) -> PathBuf { //root/a.bin, root/b.txt, root/sub/c.bin, root/skip/d.bin
+        let root = env::temp_dir().join(format!("rstrings-walk-{}", name));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::create_dir_all(root.join("skip")).unwrap();
+        for file in ["a.bin", "b.txt", "sub/c.bin", "skip/d.bin"].iter() {
+            fs::write(root.join(file), b"hello\0").unwrap();
+        }
+        return root;
+    }
+
+    fn walk(walker: FileWalker, paths: Vec<String>) -> Vec<PathBuf> {
+        return walker.walk(&paths).into_iter().filter_map(|file| file.ok()).collect();
+    }
+
+    #[test]
+    fn testWalkRecursive() {
+        let root = makeTree("recursive");
+        let found = walk(FileWalker::new().recursive(true), vec![root.to_string_lossy().into_owned()]);
+        assert_eq!(vec![root.join("a.bin"), root.join("b.txt"), root.join("skip/d.bin"), root.join("sub/c.bin")], found);
+        let found = walk(FileWalker::new().recursive(true).include("*.bin").unwrap().exclude("skip").unwrap(), vec![root.to_string_lossy().into_owned()]);
+        assert_eq!(vec![root.join("a.bin"), root.join("sub/c.bin")], found);
+    }
+
+    #[test]
+    fn testWalkNotRecursive() {
+        let root = makeTree("flat");
+        let paths = vec![root.to_string_lossy().into_owned(), root.join("b.txt").to_string_lossy().into_owned()];
+        let files = FileWalker::new().walk(&paths);
+        assert_eq!(2, files.len());
+        assert!(files[0].is_err()); //directories need --recursive
+        assert_eq!(root.join("b.txt"), *files[1].as_ref().unwrap());
+    }
+
+    #[test]
+    fn testWalkGlob() {
+        let root = makeTree("glob");
+        let found = walk(FileWalker::new(), vec![root.join("*.bin").to_string_lossy().into_owned(), root.join("missing").to_string_lossy().into_owned()]);
+        assert_eq!(vec![root.join("a.bin")], found);
+    }
+}
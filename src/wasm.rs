@@ -0,0 +1,280 @@
+use encoding::Encoding;
+use scanner::FoundString;
+use sections::{Section, readUInt, readUleb128};
+
+const MAGIC: &'static [u8] = b"\0asm\x01\0\0\0"; //version 1, the only one there is
+const SECTION_NAMES: [&'static str; 14] = ["custom", "type", "import", "function", "table", "memory", "global", "export", "start", "element", "code", "data", "datacount", "tag"];
+const CUSTOM: u8 = 0;
+const IMPORT: u8 = 2;
+const EXPORT: u8 = 7;
+const DATA: u8 = 11;
+const NAME_KINDS: [&'static str; 10] = ["module", "function", "local", "label", "type", "table", "memory", "global", "elem", "data"]; //the subsections of the name section
+
+pub fn isWasm(data: &[u8]) -> bool {
+    return data.starts_with(MAGIC);
+}
+
+fn readSleb128(data: &[u8], offset: &mut u64) -> Option<i64> {
+    let mut value: i64 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = readUInt(data, *offset, 1, false)?;
+        *offset += 1;
+        value |= ((byte & 0x7f) as i64) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                value |= -1 << shift; //negative
+            }
+            return Some(value);
+        }
+        if shift >= 70 {
+            return None;
+        }
+    }
+}
+
+fn readName(data: &[u8], offset: &mut u64) -> Option<(u64, u64)> { //a length prefixed name, returns where it is and its length and moves past it
+    let length = readUleb128(data, offset)?;
+    let start = *offset;
+    if start.checked_add(length).is_none_or(|end| end > data.len() as u64) {
+        return None;
+    }
+    *offset += length;
+    return Some((start, length));
+}
+
+fn sections(data: &[u8]) -> Vec<(u8, u64, u64)> { //the id, start and end of the contents of each section
+    let mut sections: Vec<(u8, u64, u64)> = Vec::new();
+    let mut offset = MAGIC.len() as u64;
+    while offset < data.len() as u64 {
+        let id = data[offset as usize];
+        offset += 1;
+        let size = match readUleb128(data, &mut offset) {
+            Some(size) if offset.checked_add(size).is_some_and(|end| end <= data.len() as u64) => size,
+            _ => break,
+        };
+        sections.push((id, offset, offset + size));
+        offset += size;
+    }
+    return sections;
+}
+
+/// Parses the sections of a WebAssembly module. The data section is given as its segments (named `data[0]`, `data[1]`...)
+/// with the linear memory address they are copied to (None for passive segments, and those placed by a global), custom
+/// sections as `custom:` and their name. Returns None if data isn't a WebAssembly module.
+pub fn parseSections(data: &[u8]) -> Option<Vec<Section>> {
+    if !isWasm(data) {
+        return None;
+    }
+    let mut found: Vec<Section> = Vec::new();
+    for (id, start, end) in sections(data) {
+        if id == DATA {
+            found.extend(dataSegments(data, start, end));
+            continue;
+        }
+        let mut name = String::from(*SECTION_NAMES.get(id as usize).unwrap_or(&"unknown"));
+        if id == CUSTOM {
+            let mut offset = start;
+            if let Some((nameStart, length)) = readName(data, &mut offset) {
+                name = format!("custom:{}", String::from_utf8_lossy(&data[nameStart as usize..(nameStart + length) as usize]));
+            }
+        }
+        found.push(Section { name: name, offset: start, size: end - start, address: None, imageBase: 0, isData: false });
+    }
+    return Some(found);
+}
+
+fn dataSegments(data: &[u8], start: u64, end: u64) -> Vec<Section> {
+    let mut segments: Vec<Section> = Vec::new();
+    let mut offset = start;
+    let count = readUleb128(data, &mut offset).unwrap_or(0);
+    for index in 0..count {
+        let mut address: Option<u64> = None;
+        match readUleb128(data, &mut offset) {
+            Some(0) | Some(2) => { //active: copied into memory at the start, into memory 0 unless the index of another is given
+                if data[offset as usize - 1] == 2 && readUleb128(data, &mut offset).is_none() {
+                    break;
+                }
+                let opcode = readUInt(data, offset, 1, false);
+                offset += 1;
+                if opcode == Some(0x41) || opcode == Some(0x42) { //i32.const or i64.const, anything else (like global.get) isn't known until the module runs
+                    address = readSleb128(data, &mut offset).map(|address| address as u64);
+                }
+                while offset < end && data[offset as usize] != 0x0b { //the end of the expression
+                    offset += 1;
+                }
+                offset += 1;
+            },
+            Some(1) => {}, //passive: copied when the code asks for it
+            _ => break,
+        }
+        let size = match readUleb128(data, &mut offset) {
+            Some(size) if offset.checked_add(size).is_some_and(|next| next <= end) => size,
+            _ => break,
+        };
+        segments.push(Section { name: format!("data[{}]", index), offset: offset, size: size, address: address, imageBase: 0, isData: true });
+        offset += size;
+    }
+    return segments;
+}
+
+fn nameString(data: &[u8], offset: &mut u64, tag: &str, strings: &mut Vec<FoundString>) -> Option<()> { //reads a name and adds it to strings
+    let (start, length) = readName(data, offset)?;
+    if let Ok(text) = ::std::str::from_utf8(&data[start as usize..(start + length) as usize]) {
+        if length > 0 {
+            strings.push(FoundString {
+                offset: start,
+                length: length as usize,
+                encoding: Encoding::SevenBit,
+                text: String::from(text),
+                section: Some(String::from(tag)),
+                address: None,
+                member: None,
+                stream: None,
+            });
+        }
+    }
+    return Some(());
+}
+
+fn skipLimits(data: &[u8], offset: &mut u64) -> Option<()> {
+    let flags = readUInt(data, *offset, 1, false)?;
+    *offset += 1;
+    readUleb128(data, offset)?;
+    if flags & 1 != 0 { //has a maximum
+        readUleb128(data, offset)?;
+    }
+    return Some(());
+}
+
+fn importNames(data: &[u8], mut offset: u64, strings: &mut Vec<FoundString>) -> Option<()> {
+    for _ in 0..readUleb128(data, &mut offset)? {
+        nameString(data, &mut offset, "import", strings)?; //the module
+        nameString(data, &mut offset, "import", strings)?; //the name in it
+        let kind = readUInt(data, offset, 1, false)?;
+        offset += 1;
+        match kind {
+            0 => { readUleb128(data, &mut offset)?; },          //a function, by its type
+            1 => { offset += 1; skipLimits(data, &mut offset)?; }, //a table
+            2 => skipLimits(data, &mut offset)?,                //a memory
+            3 => offset += 2,                                   //a global, its type and whether it is mutable
+            4 => { offset += 1; readUleb128(data, &mut offset)?; }, //a tag
+            _ => return None,
+        }
+    }
+    return Some(());
+}
+
+fn exportNames(data: &[u8], mut offset: u64, strings: &mut Vec<FoundString>) -> Option<()> {
+    for _ in 0..readUleb128(data, &mut offset)? {
+        nameString(data, &mut offset, "export", strings)?;
+        offset += 1; //the kind
+        readUleb128(data, &mut offset)?;
+    }
+    return Some(());
+}
+
+fn nameSection(data: &[u8], mut offset: u64, end: u64, strings: &mut Vec<FoundString>) -> Option<()> { //the names of the module, functions, locals... for debugging
+    while offset < end {
+        let kind = readUInt(data, offset, 1, false)? as usize;
+        offset += 1;
+        let size = readUleb128(data, &mut offset)?;
+        let next = offset.checked_add(size)?;
+        let tag = format!("name:{}", NAME_KINDS.get(kind).unwrap_or(&"unknown"));
+        match kind {
+            0 => { nameString(data, &mut offset, &tag, strings); },
+            2 | 3 => { //a name for each index in each function
+                for _ in 0..readUleb128(data, &mut offset)? {
+                    readUleb128(data, &mut offset)?;
+                    for _ in 0..readUleb128(data, &mut offset)? {
+                        readUleb128(data, &mut offset)?;
+                        nameString(data, &mut offset, &tag, strings)?;
+                    }
+                }
+            },
+            1 | 4..=9 => { //a name for each index
+                for _ in 0..readUleb128(data, &mut offset)? {
+                    readUleb128(data, &mut offset)?;
+                    nameString(data, &mut offset, &tag, strings)?;
+                }
+            },
+            _ => {},
+        }
+        offset = next;
+    }
+    return Some(());
+}
+
+/// Decodes the names in a WebAssembly module: those of imports (their module and name) and exports, those of custom
+/// sections and everything in the `name` section, tagged with where they came from (`import`, `export`, `custom` or
+/// `name:` and the kind of name, e.g. `name:function`). Returns the names with at least minLength characters sorted by
+/// offset, or None if data isn't a WebAssembly module.
+pub fn nameStrings(data: &[u8], minLength: usize) -> Option<Vec<FoundString>> {
+    if !isWasm(data) {
+        return None;
+    }
+    let mut strings: Vec<FoundString> = Vec::new();
+    for (id, start, end) in sections(data) {
+        match id {
+            IMPORT => { importNames(data, start, &mut strings); },
+            EXPORT => { exportNames(data, start, &mut strings); },
+            CUSTOM => {
+                let mut offset = start;
+                let isNames = readName(data, &mut offset).is_some_and(|(name, length)| &data[name as usize..(name + length) as usize] == b"name");
+                nameString(data, &mut start.clone(), "custom", &mut strings);
+                if isNames {
+                    nameSection(data, offset, end, &mut strings);
+                }
+            },
+            _ => {},
+        }
+    }
+    strings.retain(|found| found.text.chars().count() >= minLength);
+    strings.sort_by_key(|found| found.offset);
+    return Some(strings);
+}
+
+#[cfg(test)]
+mod tests {
+    use testing::readFile;
+    use super::{parseSections, nameStrings};
+
+    #[test]
+    fn testParseSections() {
+        let data = readFile("./testCases/hello.wasm");
+        let sections: Vec<(String, u64, u64, Option<u64>, bool)> = parseSections(&data).unwrap().into_iter().map(|section| (section.name, section.offset, section.size, section.address, section.isData)).collect();
+        assert_eq!(vec![
+            (String::from("type"), 0xa, 8, None, false),
+            (String::from("import"), 0x14, 35, None, false),
+            (String::from("function"), 0x39, 2, None, false),
+            (String::from("global"), 0x3d, 7, None, false),
+            (String::from("export"), 0x46, 23, None, false),
+            (String::from("code"), 0x5f, 9, None, false),
+            (String::from("data[0]"), 0x71, 30, Some(1024), true),
+            (String::from("data[1]"), 0x91, 21, None, true),
+            (String::from("data[2]"), 0xad, 14, Some(70000), true),
+            (String::from("custom:producers"), 0xbd, 33, None, false),
+            (String::from("custom:name"), 0xe0, 66, None, false),
+        ], sections);
+        assert_eq!(None, parseSections(&readFile("./testCases/a.out")));
+    }
+
+    #[test]
+    fn testNameStrings() {
+        let data = readFile("./testCases/hello.wasm");
+        let found: Vec<(String, String)> = nameStrings(&data, 4).unwrap().into_iter().map(|found| (found.section.unwrap(), found.text)).collect();
+        let found: Vec<(&str, &str)> = found.iter().map(|&(ref section, ref text)| (&section[..], &text[..])).collect();
+        assert_eq!(vec![
+            ("import", "print_message"), ("import", "memory"),
+            ("export", "run_main"), ("export", "greeting"),
+            ("custom", "producers"),
+            ("custom", "name"), ("name:module", "hello_module"), ("name:function", "print_message"), ("name:function", "run_main"), ("name:local", "message_ptr"),
+        ], found);
+        let hugeSection = [&b"\0asm\x01\0\0\0\0"[..], b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01"].concat(); //a custom section 2^64-1 bytes long
+        assert_eq!(Some(Vec::new()), nameStrings(&hugeSection, 4));
+        assert!(parseSections(&hugeSection).is_some());
+        let hugeName = [&b"\0asm\x01\0\0\0\0\x0b"[..], b"\xfe\xff\xff\xff\xff\xff\xff\xff\xff\x01x"].concat(); //a custom section named with 2^64-2 bytes
+        assert_eq!(Some(Vec::new()), nameStrings(&hugeName, 4));
+    }
+}
@@ -0,0 +1,149 @@
+use std::io::Read;
+use std::cell::Cell;
+
+use flate2::read::DeflateDecoder;
+
+use scanner::{Scanner, FoundString};
+use sections::readUInt;
+
+const LOCAL_HEADER: u64 = 0x04034b50;       //PK\3\4
+const CENTRAL_HEADER: u64 = 0x02014b50;     //PK\1\2
+const END_OF_DIRECTORY: u64 = 0x06054b50;   //PK\5\6
+const MAX_COMMENT: u64 = 0xffff;            //the end of the central directory is followed by a comment of up to this many bytes
+const STORED: u64 = 0;
+const DEFLATED: u64 = 8;
+
+/// A file in a zip archive (or a JAR, APK...).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZipMember {
+    pub name: String,
+    pub offset: u64,            //where the (compressed) contents start in the archive
+    pub compressedSize: u64,
+    pub size: u64,
+    method: u64,
+}
+
+impl ZipMember {
+    pub fn isDirectory(&self) -> bool {
+        return self.name.ends_with('/');
+    }
+
+    /// The decompressed contents of the member, cut short at limit bytes so a zip bomb can't use up the memory. None if it
+    /// is compressed with a method other than deflate or is cut off.
+    pub fn contentsUpTo(&self, data: &[u8], limit: u64) -> Option<Vec<u8>> {
+        if self.offset + self.compressedSize > data.len() as u64 {
+            return None;
+        }
+        let compressed = &data[self.offset as usize..(self.offset + self.compressedSize) as usize];
+        return match self.method {
+            STORED => Some(compressed[..if self.compressedSize > limit { limit as usize } else { compressed.len() }].to_vec()),
+            DEFLATED => {
+                let mut contents: Vec<u8> = Vec::new();
+                match DeflateDecoder::new(compressed).take(limit).read_to_end(&mut contents) {
+                    Ok(_) => Some(contents),
+                    Err(_) if contents.len() > 0 => Some(contents), //what could be decompressed before the data went bad
+                    Err(_) => None,
+                }
+            },
+            _ => None,
+        };
+    }
+}
+
+/// Lists the members of a zip archive from its central directory. Returns None if data isn't a zip archive (Zip64
+/// archives, with more than 65535 members or over 4GB, aren't understood).
+pub fn zipMembers(data: &[u8]) -> Option<Vec<ZipMember>> {
+    let read = |offset: u64, size: usize| readUInt(data, offset, size, false);
+    let length = data.len() as u64;
+    if length < 22 {
+        return None;
+    }
+    let earliest = if length > 22 + MAX_COMMENT { length - 22 - MAX_COMMENT } else { 0 };
+    let end = (earliest..length - 21).rev().find(|offset| read(*offset, 4) == Some(END_OF_DIRECTORY))?;
+    let count = read(end + 10, 2)?;
+    let mut header = read(end + 16, 4)?;
+    let mut members: Vec<ZipMember> = Vec::new();
+    for _ in 0..count {
+        if read(header, 4)? != CENTRAL_HEADER {
+            break;
+        }
+        let (nameLength, extraLength, commentLength) = (read(header + 28, 2)?, read(header + 30, 2)?, read(header + 32, 2)?);
+        let local = read(header + 42, 4)?;
+        if header + 46 + nameLength > length {
+            break;
+        }
+        let name = String::from_utf8_lossy(&data[(header + 46) as usize..(header + 46 + nameLength) as usize]).into_owned();
+        if read(local, 4) == Some(LOCAL_HEADER) { //the local header has its own (possibly different) name and extra field lengths
+            members.push(ZipMember {
+                name: name,
+                offset: local + 30 + read(local + 26, 2)? + read(local + 28, 2)?,
+                compressedSize: read(header + 20, 4)?,
+                size: read(header + 24, 4)?,
+                method: read(header + 10, 2)?,
+            });
+        }
+        header += 46 + nameLength + extraLength + commentLength;
+    }
+    return Some(members);
+}
+
+/// Finds the strings of the files in a zip archive (like a JAR or an APK) that decode understands, with the other files
+/// decompressed and searched with scanner. The strings are tagged with their file and have offsets from the start of
+/// its contents. The files are decompressed up to what's left of budget, which is reduced by what they take. Returns
+/// None if data isn't a zip archive or decode understands none of its files.
+pub fn decodeMembers<F: Fn(&[u8]) -> Option<Vec<FoundString>>>(data: &[u8], scanner: &Scanner, budget: &Cell<u64>, decode: F) -> Option<Vec<FoundString>> {
+    let mut strings: Vec<FoundString> = Vec::new();
+    let mut understood = false;
+    for member in zipMembers(data)?.iter().filter(|member| !member.isDirectory()) {
+        let contents = match member.contentsUpTo(data, budget.get()) {
+            Some(contents) => contents,
+            None => continue,
+        };
+        budget.set(budget.get() - contents.len() as u64);
+        let found = match decode(&contents) {
+            Some(found) => { understood = true; found },
+            None => scanner.scan(&contents).collect(),
+        };
+        strings.extend(found.into_iter().map(|found| FoundString { member: Some(member.name.clone()), ..found }));
+    }
+    return if understood { Some(strings) } else { None };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use scanner::{ScanOptions, Scanner};
+    use java::classStrings;
+    use testing::readFile;
+    use super::{zipMembers, decodeMembers};
+
+    #[test]
+    fn testZipMembers() {
+        let data = readFile("./testCases/hello.jar");
+        let members = zipMembers(&data).unwrap();
+        let names: Vec<&str> = members.iter().map(|member| &member.name[..]).collect();
+        assert_eq!(vec!["META-INF/", "META-INF/MANIFEST.MF", "com/example/Hello.class"], names);
+        assert!(members[0].isDirectory());
+        let manifest = members[1].contentsUpTo(&data, 1 << 20).unwrap();
+        assert_eq!(members[1].size as usize, manifest.len());
+        assert!(manifest.starts_with(b"Manifest-Version: 1.0\r\n"));
+        assert_eq!(&[0xca, 0xfe, 0xba, 0xbe], &members[2].contentsUpTo(&data, 1 << 20).unwrap()[0..4]);
+        assert_eq!(None, zipMembers(b"\x7fELF not a zip archive"));
+    }
+
+    #[test]
+    fn testDecodeMembers() {
+        let data = readFile("./testCases/hello.jar");
+        let budget = Cell::new(1 << 20);
+        let strings = decodeMembers(&data, &Scanner::new(ScanOptions::new().nullTerminated(false)), &budget, |contents| classStrings(contents, 4)).unwrap();
+        assert_eq!((1 << 20) - zipMembers(&data).unwrap().iter().map(|member| member.size).sum::<u64>(), budget.get());
+        let manifest = strings.iter().find(|found| found.member == Some(String::from("META-INF/MANIFEST.MF"))).unwrap();
+        assert!(manifest.text.starts_with("Manifest-Version: 1.0"));
+        let literal = strings.iter().find(|found| found.text == "Hello from Java").unwrap();
+        assert_eq!((Some(String::from("com/example/Hello.class")), Some(String::from("com/example/Hello:string"))), (literal.member.clone(), literal.section.clone()));
+        assert_eq!(classStrings(&readFile("./testCases/Hello.class"), 4).unwrap().len(), strings.iter().filter(|found| found.member == literal.member).count());
+        assert_eq!(None, decodeMembers(&data, &Scanner::new(ScanOptions::new()), &Cell::new(1 << 20), |_| None));
+        assert_eq!(None, decodeMembers(&data, &Scanner::new(ScanOptions::new()), &Cell::new(16), |contents| classStrings(contents, 4))); //too little to decompress the class
+        assert_eq!(None, decodeMembers(&readFile("./testCases/a.out"), &Scanner::new(ScanOptions::new()), &Cell::new(1 << 20), |contents| classStrings(contents, 4)));
+    }
+}